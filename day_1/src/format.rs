@@ -0,0 +1,232 @@
+use anyhow::{anyhow, bail, Error, Result};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Calories carried by each elf, one inner Vec per elf in input order.
+pub type Inventory = Vec<Vec<i32>>;
+
+/// The supported on-disk representations of an Inventory.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputFormat {
+    /// The puzzle format, one item per line and elves separated by a blank line.
+    Blank,
+    /// One `elf,calories` row per item, with an optional header.
+    Csv,
+    /// An array of arrays, one inner array per elf.
+    Json,
+}
+
+impl InputFormat {
+    /// Guesses the format from the content itself.
+    pub fn detect(content: &str) -> Self {
+        let trimmed = content.trim_start();
+        if trimmed.starts_with('[') {
+            InputFormat::Json
+        } else if trimmed
+            .lines()
+            .next()
+            .is_some_and(|line| line.contains(','))
+        {
+            InputFormat::Csv
+        } else {
+            InputFormat::Blank
+        }
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "blank" => Ok(InputFormat::Blank),
+            "csv" => Ok(InputFormat::Csv),
+            "json" => Ok(InputFormat::Json),
+            _ => Err(anyhow!("unknown input format '{}'", s)),
+        }
+    }
+}
+
+/// Parses an Inventory in the given format.
+pub fn parse_inventory(content: &str, format: InputFormat) -> Result<Inventory> {
+    match format {
+        InputFormat::Blank => parse_blank(content),
+        InputFormat::Csv => parse_csv(content),
+        InputFormat::Json => parse_json(content),
+    }
+}
+
+/// Writes an Inventory in the given format, such that parse_inventory() reads it back.
+pub fn write_inventory(inventory: &Inventory, format: InputFormat) -> Result<String> {
+    match format {
+        InputFormat::Blank => Ok(inventory
+            .iter()
+            .map(|elf| {
+                elf.iter()
+                    .map(|calories| calories.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+            + "\n"),
+        InputFormat::Csv => {
+            let mut out = String::from("elf,calories\n");
+            for (elf, items) in inventory.iter().enumerate() {
+                for calories in items {
+                    out += &format!("{},{}\n", elf + 1, calories);
+                }
+            }
+            Ok(out)
+        }
+        InputFormat::Json => Ok(serde_json::to_string(inventory)? + "\n"),
+    }
+}
+
+fn parse_blank(content: &str) -> Result<Inventory> {
+    let mut inventory: Inventory = vec![Vec::new()];
+    for line in content.lines() {
+        match line.trim() {
+            "" => inventory.push(Vec::new()),
+            item => {
+                let calories = item
+                    .parse::<i32>()
+                    .map_err(|why| anyhow!("failed to parse {}: {}", item, why))?;
+                if let Some(elf) = inventory.last_mut() {
+                    elf.push(calories);
+                }
+            }
+        }
+    }
+    // A trailing blank line does not start a new elf
+    if inventory.len() > 1 && inventory.last().is_some_and(|elf| elf.is_empty()) {
+        inventory.pop();
+    }
+    Ok(inventory)
+}
+
+fn parse_csv(content: &str) -> Result<Inventory> {
+    let mut inventory = Inventory::new();
+    // Each elf's position in the inventory, elves are kept in the order they first appear
+    let mut elves: HashMap<&str, usize> = HashMap::new();
+
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let fields: Vec<_> = line.split(',').map(|field| field.trim()).collect();
+        if fields.len() != 2 {
+            bail!("line {}: expected 'elf,calories', got '{}'", idx + 1, line);
+        }
+
+        // Tolerate a header row
+        if idx == 0
+            && fields[0].eq_ignore_ascii_case("elf")
+            && fields[1].eq_ignore_ascii_case("calories")
+        {
+            continue;
+        }
+
+        let calories = fields[1]
+            .parse::<i32>()
+            .map_err(|why| anyhow!("line {}: failed to parse {}: {}", idx + 1, fields[1], why))?;
+
+        // An elf's rows may be spread over the file
+        let elf = *elves.entry(fields[0]).or_insert_with(|| {
+            inventory.push(Vec::new());
+            inventory.len() - 1
+        });
+        inventory[elf].push(calories);
+    }
+    Ok(inventory)
+}
+
+fn parse_json(content: &str) -> Result<Inventory> {
+    serde_json::from_str(content).map_err(|why| anyhow!("failed to parse JSON inventory: {}", why))
+}
+
+/// Sums each elf's calories.
+pub fn totals(inventory: &Inventory) -> Vec<i32> {
+    inventory.iter().map(|elf| elf.iter().sum()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(InputFormat::detect("1\n2\n\n3"), InputFormat::Blank);
+        assert_eq!(InputFormat::detect("elf,calories\n1,2"), InputFormat::Csv);
+        assert_eq!(InputFormat::detect(" [[1,2],[3]]"), InputFormat::Json);
+    }
+
+    #[test]
+    fn test_parse_inventory() -> Result<()> {
+        let expected = vec![vec![1000, 2000], vec![4000]];
+        assert_eq!(
+            parse_inventory("1000\n2000\n\n4000\n", InputFormat::Blank)?,
+            expected
+        );
+        assert_eq!(
+            parse_inventory("elf,calories\n1,1000\n1,2000\n2,4000\n", InputFormat::Csv)?,
+            expected
+        );
+        assert_eq!(
+            parse_inventory("a,1000\na,2000\nb,4000", InputFormat::Csv)?,
+            expected
+        );
+        assert_eq!(
+            parse_inventory("[[1000, 2000], [4000]]", InputFormat::Json)?,
+            expected
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_inventory_fails() {
+        assert!(parse_inventory("1\nx\n", InputFormat::Blank).is_err());
+        assert!(parse_inventory("elf,calories\n1,x\n", InputFormat::Csv).is_err());
+        assert!(parse_inventory("1,2,3\n", InputFormat::Csv).is_err());
+        // Only the actual header may be skipped
+        assert!(parse_inventory("1,x\n2,3\n", InputFormat::Csv).is_err());
+        assert!(parse_inventory("[1, 2]", InputFormat::Json).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv_groups_elves() -> Result<()> {
+        let inventory = parse_inventory(
+            "elf,calories\n1,100\n2,5000\n1,6000\n3,1\n",
+            InputFormat::Csv,
+        )?;
+        assert_eq!(inventory, vec![vec![100, 6000], vec![5000], vec![1]]);
+        assert_eq!(totals(&inventory), [6100, 5000, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_inventory_round_trips() -> Result<()> {
+        let inventory = vec![vec![1, 2, 3], vec![4], vec![5, 6]];
+        for format in [InputFormat::Blank, InputFormat::Csv, InputFormat::Json] {
+            let written = write_inventory(&inventory, format)?;
+            assert_eq!(InputFormat::detect(&written), format);
+            assert_eq!(parse_inventory(&written, format)?, inventory);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_totals() -> Result<()> {
+        let totals_of = |content| -> Result<Vec<i32>> {
+            Ok(totals(&parse_inventory(content, InputFormat::Blank)?))
+        };
+        assert_eq!(totals_of("2\n2\n\n5")?, [4, 5]);
+        assert_eq!(totals_of("2\n2")?, [4]);
+        // A trailing newline ends the last elf. The original parser counted an extra elf carrying nothing there,
+        // which added nothing to the answer either.
+        assert_eq!(totals_of("2\n2\n\n5\n")?, [4, 5]);
+        Ok(())
+    }
+}
@@ -1,8 +1,27 @@
-use std::cmp;
+use anyhow::{anyhow, Result};
+use std::env;
 use std::fs;
 use std::path::Path;
 
-fn main() {
+mod format;
+use format::InputFormat;
+
+fn main() -> Result<()> {
+    let mut input_format: Option<InputFormat> = None;
+    let mut convert_to: Option<InputFormat> = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or(anyhow!("missing value for argument '{}'", arg))?;
+        match arg.as_str() {
+            "--input-format" => input_format = Some(value.parse()?),
+            "--convert-to" => convert_to = Some(value.parse()?),
+            _ => return Err(anyhow!("unknown argument '{}'", arg)),
+        }
+    }
+
     let input_path = Path::new("input.txt");
 
     let content = match fs::read_to_string(input_path) {
@@ -10,70 +29,19 @@ fn main() {
         Ok(content) => content,
     };
 
-    let mut calories_per_elf = parse_calorie_totals(&content);
-    calories_per_elf.sort();
-    calories_per_elf.reverse();
+    let input_format = input_format.unwrap_or_else(|| InputFormat::detect(&content));
+    let inventory = format::parse_inventory(&content, input_format)?;
 
-    println!("{}", calories_per_elf[..3].iter().sum::<i32>());
-}
-
-fn parse_maximum_calories(content: &str) -> i32 {
-    let mut max: i32 = 0;
-    let mut current_sum: i32 = 0;
-    for line in content.split("\n") {
-        match line {
-            "" => {
-                current_sum = 0;
-            }
-            _ => {
-                let val = match line.parse::<i32>() {
-                    Err(why) => panic!("failed to parse {}: {}", line, why),
-                    Ok(val) => val,
-                };
-                current_sum += val;
-                max = cmp::max(current_sum, max);
-            }
-        }
+    if let Some(output_format) = convert_to {
+        print!("{}", format::write_inventory(&inventory, output_format)?);
+        return Ok(());
     }
-    return max;
-}
 
-fn parse_calorie_totals(content: &str) -> Vec<i32> {
-    let mut totals: Vec<i32> = vec![0];
-    for line in content.split("\n") {
-        match line {
-            "" => {
-                totals.push(0);
-            }
-            _ => {
-                let val = match line.parse::<i32>() {
-                    Err(why) => panic!("failed to parse {}: {}", line, why),
-                    Ok(val) => val,
-                };
-                if let Some(current_sum) = totals.last_mut() {
-                    *current_sum += val;
-                }
-            }
-        }
-    }
-    return totals;
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+    let mut calories_per_elf = format::totals(&inventory);
+    calories_per_elf.sort();
+    calories_per_elf.reverse();
 
-    #[test]
-    fn test_parse_maximum_calories() {
-        assert_eq!(parse_maximum_calories("2\n2\n\n5"), 5);
-        assert_eq!(parse_maximum_calories("2\n2\n\n5\n"), 5);
-        assert_eq!(parse_maximum_calories("2\n2"), 4);
-    }
+    println!("{}", calories_per_elf.iter().take(3).sum::<i32>());
 
-    #[test]
-    fn test_parse_calorie_totals() {
-        assert_eq!(parse_calorie_totals("2\n2\n\n5"), [4, 5]);
-        assert_eq!(parse_calorie_totals("2\n2\n\n5\n"), [4, 5, 0]);
-        assert_eq!(parse_calorie_totals("2\n2"), [4]);
-    }
+    Ok(())
 }
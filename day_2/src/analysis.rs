@@ -0,0 +1,190 @@
+use crate::game::Game;
+use crate::scoring::ScoringTable;
+use crate::Outcome;
+use std::cmp::Reverse;
+
+/// Finds the best and worst scores achievable against a known sequence of opponent throws.
+pub struct Analyzer<'a> {
+    game: &'a Game,
+    scoring: &'a ScoringTable,
+}
+
+impl<'a> Analyzer<'a> {
+    pub fn new(game: &'a Game, scoring: &'a ScoringTable) -> Self {
+        Self { game, scoring }
+    }
+
+    /// Returns the score-maximizing throw against `theirs`, preferring the earliest throw on ties.
+    pub fn best_throw(&self, theirs: usize) -> usize {
+        (0..self.game.len())
+            .max_by_key(|mine| (self.scoring.score(self.game, *mine, theirs), Reverse(*mine)))
+            .unwrap_or(0)
+    }
+
+    /// Returns the score-minimizing throw against `theirs`, preferring the earliest throw on ties.
+    pub fn worst_throw(&self, theirs: usize) -> usize {
+        (0..self.game.len())
+            .min_by_key(|mine| (self.scoring.score(self.game, *mine, theirs), *mine))
+            .unwrap_or(0)
+    }
+
+    pub fn best_throws(&self, theirs: &[usize]) -> Vec<usize> {
+        theirs.iter().map(|t| self.best_throw(*t)).collect()
+    }
+
+    pub fn total(&self, mine: &[usize], theirs: &[usize]) -> i32 {
+        mine.iter()
+            .zip(theirs)
+            .map(|(m, t)| self.scoring.score(self.game, *m, *t))
+            .sum()
+    }
+
+    pub fn best_total(&self, theirs: &[usize]) -> i32 {
+        self.total(&self.best_throws(theirs), theirs)
+    }
+
+    pub fn worst_total(&self, theirs: &[usize]) -> i32 {
+        let worst: Vec<_> = theirs.iter().map(|t| self.worst_throw(*t)).collect();
+        self.total(&worst, theirs)
+    }
+
+    /// Returns the best throws and their total when at most `max_wins` rounds may be won.
+    ///
+    /// Solved by dynamic programming over the rounds, tracking the best total for each number of wins used so far.
+    pub fn best_with_max_wins(&self, theirs: &[usize], max_wins: usize) -> (i32, Vec<usize>) {
+        let max_wins = max_wins.min(theirs.len());
+
+        // The best winning and non-winning throw of each round; a draw is always possible so the latter exists
+        let choices: Vec<(Option<usize>, usize)> = theirs
+            .iter()
+            .map(|t| {
+                let best_of = |want_win: bool| {
+                    (0..self.game.len())
+                        .filter(|m| (self.game.outcome(*m, *t) == Outcome::Win) == want_win)
+                        .max_by_key(|m| (self.scoring.score(self.game, *m, *t), Reverse(*m)))
+                };
+                (best_of(true), best_of(false).unwrap_or(*t))
+            })
+            .collect();
+
+        // best[w] is the best total so far using exactly w wins, None when unreachable
+        let mut best: Vec<Option<i32>> = vec![None; max_wins + 1];
+        best[0] = Some(0);
+        // won[i][w] records whether round i was won on the best path reaching w wins after it
+        let mut won: Vec<Vec<bool>> = Vec::with_capacity(theirs.len());
+
+        for (t, (win, other)) in theirs.iter().zip(&choices) {
+            let other_points = self.scoring.score(self.game, *other, *t);
+            let win_points = win.map(|w| self.scoring.score(self.game, w, *t));
+
+            let mut next: Vec<Option<i32>> = vec![None; max_wins + 1];
+            let mut round_won = vec![false; max_wins + 1];
+            for w in 0..=max_wins {
+                let without = best[w].map(|total| total + other_points);
+                let with = match (w, win_points) {
+                    (1.., Some(points)) => best[w - 1].map(|total| total + points),
+                    _ => None,
+                };
+                if with > without {
+                    next[w] = with;
+                    round_won[w] = true;
+                } else {
+                    next[w] = without;
+                }
+            }
+            best = next;
+            won.push(round_won);
+        }
+
+        let (mut wins, total) = best
+            .iter()
+            .enumerate()
+            .filter_map(|(w, total)| total.map(|total| (w, total)))
+            .max_by_key(|(w, total)| (*total, Reverse(*w)))
+            .unwrap_or((0, 0));
+
+        let mut throws = vec![0; theirs.len()];
+        for i in (0..theirs.len()).rev() {
+            let (win, other) = choices[i];
+            if won[i][wins] {
+                throws[i] = win.unwrap_or(other);
+                wins -= 1;
+            } else {
+                throws[i] = other;
+            }
+        }
+        (total, throws)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The puzzle's example opponent column: Rock, Paper, Scissors
+    const THEIRS: [usize; 3] = [0, 1, 2];
+
+    #[test]
+    fn test_best_and_worst() {
+        let game = Game::rps();
+        let scoring = ScoringTable::standard(game);
+        let analyzer = Analyzer::new(game, &scoring);
+
+        // Paper beats Rock for 8, Scissors beats Paper for 9, Rock beats Scissors for 7
+        assert_eq!(analyzer.best_throws(&THEIRS), [1, 2, 0]);
+        assert_eq!(analyzer.best_total(&THEIRS), 24);
+        // Scissors loses to Rock for 3, Rock loses to Paper for 1, Paper loses to Scissors for 2
+        assert_eq!(analyzer.worst_total(&THEIRS), 6);
+    }
+
+    #[test]
+    fn test_best_with_max_wins() {
+        let game = Game::rps();
+        let scoring = ScoringTable::standard(game);
+        let analyzer = Analyzer::new(game, &scoring);
+
+        assert_eq!(analyzer.best_with_max_wins(&THEIRS, 3), (24, vec![1, 2, 0]));
+        // Without wins the best is drawing every round
+        assert_eq!(analyzer.best_with_max_wins(&THEIRS, 0), (15, vec![0, 1, 2]));
+        // One win should be spent where it gains the most over not winning
+        let (total, throws) = analyzer.best_with_max_wins(&THEIRS, 1);
+        assert_eq!(total, analyzer.total(&throws, &THEIRS));
+        assert_eq!(
+            throws
+                .iter()
+                .zip(THEIRS)
+                .filter(|(m, t)| game.outcome(**m, *t) == Outcome::Win)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_best_with_max_wins_matches_brute_force() {
+        let game = Game::rps();
+        let scoring = ScoringTable::standard(game);
+        let analyzer = Analyzer::new(game, &scoring);
+        let theirs = [2, 0, 0, 1, 2, 1];
+
+        for max_wins in 0..=theirs.len() {
+            let mut brute_force = i32::MIN;
+            for code in 0..3usize.pow(theirs.len() as u32) {
+                let mine: Vec<usize> = (0..theirs.len())
+                    .map(|i| code / 3usize.pow(i as u32) % 3)
+                    .collect();
+                let wins = mine
+                    .iter()
+                    .zip(theirs)
+                    .filter(|(m, t)| game.outcome(**m, *t) == Outcome::Win)
+                    .count();
+                if wins <= max_wins {
+                    brute_force = brute_force.max(analyzer.total(&mine, &theirs));
+                }
+            }
+            assert_eq!(
+                analyzer.best_with_max_wins(&theirs, max_wins).0,
+                brute_force
+            );
+        }
+    }
+}
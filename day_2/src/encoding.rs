@@ -0,0 +1,311 @@
+use crate::game::Game;
+use crate::{get_throw, Match, Outcome, Throw};
+use anyhow::{anyhow, Error, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// How the second column of the strategy guide is read.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    /// The second column is the throw we make.
+    Throws,
+    /// The second column is the outcome we want, our throw is derived from it.
+    Outcomes,
+}
+
+impl FromStr for Mode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "throws" => Ok(Mode::Throws),
+            "outcomes" => Ok(Mode::Outcomes),
+            _ => Err(anyhow!("unknown mode '{}'", s)),
+        }
+    }
+}
+
+/// Maps the letters of a strategy guide onto throws and outcomes.
+///
+/// Loaded from TOML, any table left out keeps the puzzle's encoding:
+/// ```toml
+/// [theirs]
+/// A = "Rock"
+/// [mine]
+/// X = "Rock"
+/// [outcomes]
+/// X = "Lose"
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Encoding {
+    /// The opponent's column.
+    pub theirs: HashMap<String, Throw>,
+    /// Our column when read as throws.
+    pub mine: HashMap<String, Throw>,
+    /// Our column when read as outcomes.
+    pub outcomes: HashMap<String, Outcome>,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        let throws = |letters: [&str; 3]| {
+            letters
+                .into_iter()
+                .map(String::from)
+                .zip([Throw::Rock, Throw::Paper, Throw::Scissors])
+                .collect()
+        };
+        Self {
+            theirs: throws(["A", "B", "C"]),
+            mine: throws(["X", "Y", "Z"]),
+            outcomes: ["X", "Y", "Z"]
+                .into_iter()
+                .map(String::from)
+                .zip([Outcome::Lose, Outcome::Draw, Outcome::Win])
+                .collect(),
+        }
+    }
+}
+
+impl Encoding {
+    /// The puzzle's encoding, shared so it's only built once.
+    pub fn standard() -> &'static Self {
+        static STANDARD: OnceLock<Encoding> = OnceLock::new();
+        STANDARD.get_or_init(Self::default)
+    }
+
+    /// Parses an Encoding from its TOML representation.
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let encoding: Self = toml::from_str(content)?;
+        encoding.validate()?;
+        Ok(encoding)
+    }
+
+    /// Checks that every column maps to each of its values exactly once.
+    fn validate(&self) -> Result<()> {
+        check_bijective("theirs", &self.theirs, 3)?;
+        check_bijective("mine", &self.mine, 3)?;
+        check_bijective("outcomes", &self.outcomes, 3)?;
+        Ok(())
+    }
+
+    /// Parses one line of the strategy guide.
+    pub fn parse_match(&self, line: &str, mode: Mode) -> Result<Match> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 2 {
+            return Err(anyhow!("expected two columns, got '{}'", line));
+        }
+
+        let theirs = self.their_throw(fields[0])?;
+        let mine = match mode {
+            Mode::Throws => self.my_throw(fields[1])?,
+            Mode::Outcomes => get_throw(theirs, self.outcome(fields[1])?),
+        };
+
+        Ok(Match { mine, theirs })
+    }
+
+    /// Reads a letter of the opponent's column.
+    pub fn their_throw(&self, letter: &str) -> Result<Throw> {
+        self.theirs
+            .get(letter)
+            .copied()
+            .ok_or(anyhow!("unknown opponent throw '{}'", letter))
+    }
+
+    /// Reads a letter of our column as the throw we make.
+    pub fn my_throw(&self, letter: &str) -> Result<Throw> {
+        self.mine
+            .get(letter)
+            .copied()
+            .ok_or(anyhow!("unknown throw '{}'", letter))
+    }
+
+    /// Reads a letter of our column as the outcome we want.
+    pub fn outcome(&self, letter: &str) -> Result<Outcome> {
+        self.outcomes
+            .get(letter)
+            .copied()
+            .ok_or(anyhow!("unknown outcome '{}'", letter))
+    }
+
+    /// Returns the letter of the opponent's column for `throw`.
+    pub fn their_letter(&self, throw: Throw) -> Result<&str> {
+        letter_for(&self.theirs, &throw)
+    }
+
+    /// Returns the letter of our column for `outcome`.
+    pub fn outcome_letter(&self, outcome: Outcome) -> Result<&str> {
+        letter_for(&self.outcomes, &outcome)
+    }
+
+    /// Writes one Match as a strategy guide line that parse_match() reads back in the same mode.
+    pub fn encode_match(&self, m: &Match, mode: Mode) -> Result<String> {
+        let theirs = self.their_letter(m.theirs)?;
+        let mine = match mode {
+            Mode::Throws => letter_for(&self.mine, &m.mine)?,
+            Mode::Outcomes => {
+                self.outcome_letter(Game::rps().outcome(m.mine.index(), m.theirs.index()))?
+            }
+        };
+        Ok(format!("{} {}", theirs, mine))
+    }
+
+    /// Writes a whole strategy guide, one line per Match.
+    pub fn encode_guide(&self, matches: &[Match], mode: Mode) -> Result<String> {
+        matches
+            .iter()
+            .map(|m| Ok(self.encode_match(m, mode)? + "\n"))
+            .collect()
+    }
+}
+
+/// Builds the matches that achieve each desired outcome against the opponent's throw of the same round.
+pub fn plan_matches(theirs: &[Throw], outcomes: &[Outcome]) -> Result<Vec<Match>> {
+    if theirs.len() != outcomes.len() {
+        return Err(anyhow!(
+            "got {} opponent throws but {} desired outcomes",
+            theirs.len(),
+            outcomes.len()
+        ));
+    }
+    Ok(theirs
+        .iter()
+        .zip(outcomes)
+        .map(|(theirs, outcome)| Match {
+            theirs: *theirs,
+            mine: get_throw(*theirs, *outcome),
+        })
+        .collect())
+}
+
+fn letter_for<'a, T: PartialEq + std::fmt::Debug>(
+    mapping: &'a HashMap<String, T>,
+    value: &T,
+) -> Result<&'a str> {
+    mapping
+        .iter()
+        .find(|(_, each)| *each == value)
+        .map(|(letter, _)| letter.as_str())
+        .ok_or(anyhow!("no letter encodes {:?}", value))
+}
+
+fn check_bijective<T: PartialEq>(
+    column: &str,
+    mapping: &HashMap<String, T>,
+    expected: usize,
+) -> Result<()> {
+    if mapping.len() != expected {
+        return Err(anyhow!(
+            "[{}] must map exactly {} letters, got {}",
+            column,
+            expected,
+            mapping.len()
+        ));
+    }
+    for (letter, value) in mapping {
+        if mapping
+            .iter()
+            .any(|(other, other_value)| other != letter && other_value == value)
+        {
+            return Err(anyhow!("[{}] maps several letters to one value", column));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scoring::ScoringTable;
+
+    #[test]
+    fn test_parse_match() -> Result<()> {
+        let encoding = Encoding::default();
+        let scoring = ScoringTable::standard(Game::rps());
+
+        let m = encoding.parse_match("A Y", Mode::Outcomes)?;
+        assert_eq!((m.theirs, m.mine), (Throw::Rock, Throw::Rock));
+        assert_eq!(m.calculate_score(&scoring), 4);
+
+        let m = encoding.parse_match("A Y", Mode::Throws)?;
+        assert_eq!((m.theirs, m.mine), (Throw::Rock, Throw::Paper));
+        assert_eq!(m.calculate_score(&scoring), 8);
+
+        assert!(encoding.parse_match("D X", Mode::Throws).is_err());
+        assert!(encoding.parse_match("A", Mode::Throws).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_toml() -> Result<()> {
+        let encoding = Encoding::from_toml(
+            r#"
+            [theirs]
+            R = "Rock"
+            P = "Paper"
+            S = "Scissors"
+            [outcomes]
+            L = "Lose"
+            D = "Draw"
+            W = "Win"
+            "#,
+        )?;
+        let m = encoding.parse_match("P W", Mode::Outcomes)?;
+        assert_eq!((m.theirs, m.mine), (Throw::Paper, Throw::Scissors));
+        // Desired outcomes are read with the same letters
+        assert_eq!(encoding.outcome("W")?, Outcome::Win);
+        assert!(encoding.outcome("Z").is_err());
+
+        // Tables left out keep the puzzle's letters
+        let m = encoding.parse_match("S X", Mode::Throws)?;
+        assert_eq!((m.theirs, m.mine), (Throw::Scissors, Throw::Rock));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_toml_fails() {
+        assert!(Encoding::from_toml("[theirs]\nA = \"Rock\"").is_err());
+        assert!(
+            Encoding::from_toml("[theirs]\nA = \"Rock\"\nB = \"Rock\"\nC = \"Paper\"").is_err()
+        );
+        assert!(Encoding::from_toml("[theirs]\nA = \"Lizard\"").is_err());
+        assert!(Encoding::from_toml("[unknown]\nA = \"Rock\"").is_err());
+    }
+
+    #[test]
+    fn test_encode_guide_round_trips() -> Result<()> {
+        let theirs = [Throw::Rock, Throw::Paper, Throw::Scissors, Throw::Rock];
+        let outcomes = [Outcome::Win, Outcome::Draw, Outcome::Lose, Outcome::Lose];
+        let matches = plan_matches(&theirs, &outcomes)?;
+
+        let encoding =
+            Encoding::from_toml("[theirs]\nR = \"Rock\"\nP = \"Paper\"\nS = \"Scissors\"")?;
+        for encoding in [Encoding::default(), encoding] {
+            for mode in [Mode::Throws, Mode::Outcomes] {
+                let guide = encoding.encode_guide(&matches, mode)?;
+                let parsed = guide
+                    .lines()
+                    .map(|line| encoding.parse_match(line, mode))
+                    .collect::<Result<Vec<_>>>()?;
+                assert_eq!(parsed, matches);
+            }
+        }
+
+        // The default encoding in outcome mode is the puzzle's own format
+        let guide = Encoding::default().encode_guide(&matches, Mode::Outcomes)?;
+        assert_eq!(guide, "A Z\nB Y\nC X\nA X\n");
+        for (line, m) in guide.lines().zip(&matches) {
+            assert_eq!(line.parse::<Match>()?, *m);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_matches_fails() {
+        assert!(plan_matches(&[Throw::Rock], &[]).is_err());
+    }
+}
@@ -0,0 +1,317 @@
+use crate::encoding::{Encoding, Mode};
+use crate::scoring::ScoringTable;
+use crate::Outcome;
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// A hand game where every pair of distinct throws has exactly one winner.
+///
+/// Throws are referred to by their index, which also orders their scores: the first throw is worth 1 point, the
+/// second 2, and so on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Game {
+    names: Vec<String>,
+    /// `dominates[a][b]` is true when throw a beats throw b.
+    dominates: Vec<Vec<bool>>,
+}
+
+/// The serialized form of a user-supplied dominance table.
+///
+/// ```toml
+/// throws = ["Rock", "Paper", "Scissors"]
+/// [beats]
+/// Rock = ["Scissors"]
+/// Paper = ["Rock"]
+/// Scissors = ["Paper"]
+/// ```
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Definition {
+    throws: Vec<String>,
+    beats: HashMap<String, Vec<String>>,
+}
+
+impl Game {
+    /// Builds the balanced cyclic game on an odd number of throws.
+    ///
+    /// Each throw beats the throws an odd distance behind it, so with Rock, Paper, Scissors, Paper beats Rock.
+    pub fn cyclic(names: &[&str]) -> Result<Self> {
+        let n = names.len();
+        if n < 3 || n.is_multiple_of(2) {
+            bail!("a cyclic game needs an odd number of throws, got {}", n);
+        }
+        let dominates = (0..n)
+            .map(|a| (0..n).map(|b| (a + n - b) % n % 2 == 1).collect())
+            .collect();
+        Self::from_table(
+            names.iter().map(|name| name.to_string()).collect(),
+            dominates,
+        )
+    }
+
+    /// Builds a game from an explicit dominance table, checking that every pair of throws has exactly one winner.
+    pub fn from_table(names: Vec<String>, dominates: Vec<Vec<bool>>) -> Result<Self> {
+        let n = names.len();
+        if n == 0 {
+            bail!("a game needs at least one throw");
+        }
+        if dominates.len() != n || dominates.iter().any(|row| row.len() != n) {
+            bail!("dominance table must be {}x{}", n, n);
+        }
+        for a in 0..n {
+            if names[..a].contains(&names[a]) {
+                bail!("throw '{}' is defined twice", names[a]);
+            }
+            if dominates[a][a] {
+                bail!("'{}' cannot beat itself", names[a]);
+            }
+            for b in (a + 1)..n {
+                if dominates[a][b] == dominates[b][a] {
+                    bail!(
+                        "exactly one of '{}' and '{}' must beat the other",
+                        names[a],
+                        names[b]
+                    );
+                }
+            }
+        }
+        Ok(Self { names, dominates })
+    }
+
+    /// Parses a game from its TOML dominance table.
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let definition: Definition = toml::from_str(content)?;
+        let n = definition.throws.len();
+        let mut dominates = vec![vec![false; n]; n];
+        for (winner, losers) in &definition.beats {
+            let a = index_in(&definition.throws, winner)?;
+            for loser in losers {
+                dominates[a][index_in(&definition.throws, loser)?] = true;
+            }
+        }
+        Self::from_table(definition.throws, dominates)
+    }
+
+    /// Rock, Paper, Scissors.
+    pub fn rps() -> &'static Self {
+        static RPS: OnceLock<Game> = OnceLock::new();
+        RPS.get_or_init(|| Self::cyclic(&["Rock", "Paper", "Scissors"]).unwrap())
+    }
+
+    /// Rock, Paper, Scissors, Spock, Lizard.
+    pub fn rpsls() -> &'static Self {
+        static RPSLS: OnceLock<Game> = OnceLock::new();
+        RPSLS.get_or_init(|| {
+            Self::cyclic(&["Rock", "Paper", "Scissors", "Spock", "Lizard"]).unwrap()
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name(&self, throw: usize) -> &str {
+        &self.names[throw]
+    }
+
+    pub fn index_of(&self, name: &str) -> Result<usize> {
+        index_in(&self.names, name)
+    }
+
+    /// Returns the throws that `throw` defeats.
+    pub fn beats(&self, throw: usize) -> Vec<usize> {
+        (0..self.len())
+            .filter(|other| self.dominates[throw][*other])
+            .collect()
+    }
+
+    /// Returns the throws that defeat `throw`.
+    pub fn loses_to(&self, throw: usize) -> Vec<usize> {
+        (0..self.len())
+            .filter(|other| self.dominates[*other][throw])
+            .collect()
+    }
+
+    pub fn outcome(&self, mine: usize, theirs: usize) -> Outcome {
+        if self.dominates[mine][theirs] {
+            Outcome::Win
+        } else if self.dominates[theirs][mine] {
+            Outcome::Lose
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// Returns every throw that achieves `outcome` against `theirs`.
+    pub fn throws_for(&self, theirs: usize, outcome: Outcome) -> Vec<usize> {
+        match outcome {
+            Outcome::Win => self.loses_to(theirs),
+            Outcome::Lose => self.beats(theirs),
+            Outcome::Draw => vec![theirs],
+        }
+    }
+
+    /// Parses one strategy guide line naming throws by name, e.g. `Rock Spock`, into `(mine, theirs)`.
+    ///
+    /// In `Mode::Outcomes` the second column uses the encoding's outcome letters, and the throw that achieves the
+    /// outcome for the most points under `scoring` is picked.
+    pub fn parse_round(
+        &self,
+        line: &str,
+        mode: Mode,
+        encoding: &Encoding,
+        scoring: &ScoringTable,
+    ) -> Result<(usize, usize)> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 2 {
+            bail!("expected two columns, got '{}'", line);
+        }
+
+        let theirs = self.index_of(fields[0])?;
+        let mine = match mode {
+            Mode::Throws => self.index_of(fields[1])?,
+            Mode::Outcomes => {
+                let outcome = *encoding
+                    .outcomes
+                    .get(fields[1])
+                    .ok_or(anyhow!("unknown outcome '{}'", fields[1]))?;
+                self.throws_for(theirs, outcome)
+                    .into_iter()
+                    .max_by_key(|throw| scoring.score(self, *throw, theirs))
+                    .ok_or(anyhow!(
+                        "no throw achieves {:?} against '{}'",
+                        outcome,
+                        self.name(theirs)
+                    ))?
+            }
+        };
+        Ok((mine, theirs))
+    }
+
+    /// Returns the points a throw is worth on its own.
+    pub fn throw_score(&self, throw: usize) -> i32 {
+        throw as i32 + 1
+    }
+}
+
+fn index_in(names: &[String], name: &str) -> Result<usize> {
+    names
+        .iter()
+        .position(|each| each == name)
+        .ok_or(anyhow!("unknown throw '{}'", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rps() -> Result<()> {
+        let game = Game::rps();
+        let (rock, paper, scissors) = (
+            game.index_of("Rock")?,
+            game.index_of("Paper")?,
+            game.index_of("Scissors")?,
+        );
+        assert_eq!(game.beats(rock), [scissors]);
+        assert_eq!(game.loses_to(rock), [paper]);
+        assert_eq!(game.outcome(paper, rock), Outcome::Win);
+        assert_eq!(game.outcome(paper, scissors), Outcome::Lose);
+        assert_eq!(game.throws_for(scissors, Outcome::Win), [rock]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rpsls() -> Result<()> {
+        let game = Game::rpsls();
+        let spock = game.index_of("Spock")?;
+        let mut beaten: Vec<_> = game.beats(spock).iter().map(|t| game.name(*t)).collect();
+        beaten.sort();
+        assert_eq!(beaten, ["Rock", "Scissors"]);
+        for throw in 0..game.len() {
+            assert_eq!(game.beats(throw).len(), 2);
+            assert_eq!(game.loses_to(throw).len(), 2);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_round() -> Result<()> {
+        let game = Game::rpsls();
+        let encoding = Encoding::default();
+        let scoring = ScoringTable::standard(game);
+        let (rock, spock, lizard) = (
+            game.index_of("Rock")?,
+            game.index_of("Spock")?,
+            game.index_of("Lizard")?,
+        );
+        assert_eq!(
+            game.parse_round("Rock Spock", Mode::Throws, &encoding, &scoring)?,
+            (spock, rock)
+        );
+        // Both Paper and Spock beat Rock, Spock scores higher
+        assert_eq!(
+            game.parse_round("Rock Z", Mode::Outcomes, &encoding, &scoring)?,
+            (spock, rock)
+        );
+        assert_eq!(
+            game.parse_round("Rock X", Mode::Outcomes, &encoding, &scoring)?,
+            (lizard, rock)
+        );
+        assert!(game
+            .parse_round("Rock Banana", Mode::Throws, &encoding, &scoring)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_round_uses_scoring() -> Result<()> {
+        let game = Game::rpsls();
+        let encoding = Encoding::default();
+        let (rock, paper) = (game.index_of("Rock")?, game.index_of("Paper")?);
+        let scoring = ScoringTable::from_toml(
+            "[throws]\nRock = 1\nPaper = 50\nScissors = 3\nSpock = 4\nLizard = 5\n\
+             [outcomes]\nwin = 6\ndraw = 3\nlose = 0",
+            game,
+        )?;
+        // Paper now outscores Spock
+        assert_eq!(
+            game.parse_round("Rock Z", Mode::Outcomes, &encoding, &scoring)?,
+            (paper, rock)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_cyclic_fails() {
+        assert!(Game::cyclic(&["Rock", "Paper"]).is_err());
+        assert!(Game::cyclic(&["Rock", "Rock", "Rock"]).is_err());
+    }
+
+    #[test]
+    fn test_from_toml() -> Result<()> {
+        let game = Game::from_toml(
+            r#"
+            throws = ["Rock", "Paper", "Scissors"]
+            [beats]
+            Rock = ["Scissors"]
+            Paper = ["Rock"]
+            Scissors = ["Paper"]
+            "#,
+        )?;
+        assert_eq!(&game, Game::rps());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_toml_fails() {
+        // Nobody wins between Rock and Scissors
+        assert!(Game::from_toml(
+            "throws = [\"Rock\", \"Paper\", \"Scissors\"]\n[beats]\nPaper = [\"Rock\"]\nScissors = [\"Paper\"]"
+        )
+        .is_err());
+        assert!(Game::from_toml("throws = [\"Rock\"]\n[beats]\nRock = [\"Lizard\"]").is_err());
+    }
+}
@@ -1,10 +1,71 @@
-use std::error;
+use anyhow::{anyhow, bail, Error, Result};
+use serde::Deserialize;
+use std::env;
 use std::fmt;
 use std::fs;
 use std::path::Path;
 use std::str::FromStr;
 
-fn main() {
+mod analysis;
+mod encoding;
+mod game;
+mod report;
+mod scoring;
+mod strategy;
+mod tournament;
+use analysis::Analyzer;
+use encoding::{Encoding, Mode};
+use game::Game;
+use report::{Report, ReportFormat, Round};
+use scoring::ScoringTable;
+use strategy::{FixedGuide, FrequencyCounter, MarkovPredictor, RandomThrows, Strategy};
+use tournament::Leaderboard;
+
+fn main() -> Result<()> {
+    let mut mode = Mode::Outcomes;
+    let mut encoding = Encoding::default();
+    let mut game: Option<Game> = None;
+    let mut scoring_path: Option<String> = None;
+    let mut report_format: Option<ReportFormat> = None;
+    let mut tournament_rounds: Option<usize> = None;
+    let mut seed: u64 = 0;
+    let mut analyze = false;
+    let mut desired_path: Option<String> = None;
+    let mut max_wins: Option<usize> = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or(anyhow!("missing value for argument '{}'", arg))
+        };
+        match arg.as_str() {
+            "--mode" => mode = value()?.parse()?,
+            "--encoding" => encoding = Encoding::from_toml(&fs::read_to_string(value()?)?)?,
+            "--game" => {
+                game = Some(match value()?.as_str() {
+                    "rps" => Game::rps().clone(),
+                    "rpsls" => Game::rpsls().clone(),
+                    path => Game::from_toml(&fs::read_to_string(path)?)?,
+                })
+            }
+            "--scoring" => scoring_path = Some(value()?),
+            "--report" => report_format = Some(value()?.parse()?),
+            "--tournament" => tournament_rounds = Some(value()?.parse()?),
+            "--seed" => seed = value()?.parse()?,
+            "--analyze" => analyze = true,
+            "--desired" => desired_path = Some(value()?),
+            "--max-wins" => max_wins = Some(value()?.parse()?),
+            _ => return Err(anyhow!("unknown argument '{}'", arg)),
+        }
+    }
+
+    let selected_game = game.as_ref().unwrap_or(Game::rps());
+    let scoring = match &scoring_path {
+        Some(path) => ScoringTable::load(Path::new(path), selected_game)?,
+        None => ScoringTable::standard(selected_game),
+    };
+
     let input_path = Path::new("input.txt");
 
     let content = match fs::read_to_string(input_path) {
@@ -12,107 +73,238 @@ fn main() {
         Ok(content) => content,
     };
 
-    let score: i32 = content
-        .split("\n")
-        .map(|line| Match::from_str(line).calculate_score())
-        .sum();
+    let lines = content.lines().filter(|line| !line.is_empty());
+    let rounds: Vec<Round> = match &game {
+        Some(game) => lines
+            .map(|line| {
+                let (mine, theirs) = game.parse_round(line, mode, &encoding, &scoring)?;
+                Ok(Round::new(game, &scoring, mine, theirs))
+            })
+            .collect::<Result<_>>()?,
+        None => lines
+            .map(|line| Ok(encoding.parse_match(line, mode)?.to_round(&scoring)))
+            .collect::<Result<_>>()?,
+    };
+
+    if let Some(path) = desired_path {
+        if game.is_some() {
+            bail!("guides can only be written for Rock, Paper, Scissors");
+        }
+        let theirs: Vec<Throw> = rounds
+            .iter()
+            .map(|round| Throw::from_index(round.theirs))
+            .collect();
+        let outcomes: Vec<Outcome> = fs::read_to_string(&path)?
+            .split_whitespace()
+            .map(|token| encoding.outcome(token))
+            .collect::<Result<_>>()?;
+        let matches = encoding::plan_matches(&theirs, &outcomes)?;
+        print!("{}", encoding.encode_guide(&matches, mode)?);
+        return Ok(());
+    }
+
+    if let Some(rounds_per_match) = tournament_rounds {
+        if game.is_some() || rounds.is_empty() {
+            bail!("a tournament needs a non-empty Rock, Paper, Scissors guide");
+        }
+        let guide = rounds
+            .iter()
+            .map(|round| Throw::from_index(round.mine))
+            .collect();
+        let mut strategies: Vec<Box<dyn Strategy>> = vec![
+            Box::new(FixedGuide::new("guide", guide)),
+            Box::new(FrequencyCounter),
+            Box::new(MarkovPredictor),
+            Box::new(RandomThrows::new(seed)),
+        ];
+        let standings = tournament::round_robin(&mut strategies, rounds_per_match, &scoring);
+        print!("{}", Leaderboard(&standings));
+        return Ok(());
+    }
 
-    println!("Total: {}", score);
+    if analyze {
+        let analyzer = Analyzer::new(selected_game, &scoring);
+        let theirs: Vec<usize> = rounds.iter().map(|round| round.theirs).collect();
+        let guide: i32 = rounds.iter().map(|round| round.points).sum();
+        let best = analyzer.best_total(&theirs);
+
+        println!("Guide: {}", guide);
+        println!("Best: {} ({} short of optimal)", best, best - guide);
+        println!("Worst: {}", analyzer.worst_total(&theirs));
+        if let Some(max_wins) = max_wins {
+            let (total, _) = analyzer.best_with_max_wins(&theirs, max_wins);
+            println!("Best with at most {} wins: {}", max_wins, total);
+        }
+        return Ok(());
+    }
+
+    let report = Report::new(selected_game, rounds);
+
+    match report_format {
+        Some(format) => println!("{}", report.render(format).trim_end()),
+        None => println!("Total: {}", report.total()),
+    }
+
+    Ok(())
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
 enum Throw {
     Rock,
     Paper,
     Scissors,
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
 enum Outcome {
     Win,
     Lose,
     Draw,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 struct Match {
     mine: Throw,
     theirs: Throw,
 }
 
-fn get_throw(theirs: Throw, outcome: Outcome) -> Throw {
-    match theirs {
-        Throw::Rock => match outcome {
-            Outcome::Win => Throw::Paper,
-            Outcome::Lose => Throw::Scissors,
-            Outcome::Draw => theirs,
-        },
-        Throw::Paper => match outcome {
-            Outcome::Win => Throw::Scissors,
-            Outcome::Lose => Throw::Rock,
-            Outcome::Draw => theirs,
-        },
-        Throw::Scissors => match outcome {
-            Outcome::Win => Throw::Rock,
-            Outcome::Lose => Throw::Paper,
-            Outcome::Draw => theirs,
-        },
+impl Throw {
+    /// Returns this throw's index in `Game::rps()`.
+    fn index(self) -> usize {
+        match self {
+            Throw::Rock => 0,
+            Throw::Paper => 1,
+            Throw::Scissors => 2,
+        }
+    }
+
+    fn from_index(idx: usize) -> Self {
+        match idx {
+            0 => Throw::Rock,
+            1 => Throw::Paper,
+            2 => Throw::Scissors,
+            _ => panic!("no throw at index {}", idx),
+        }
     }
 }
 
+fn get_throw(theirs: Throw, outcome: Outcome) -> Throw {
+    // Rock, Paper, Scissors has exactly one throw for every outcome
+    Throw::from_index(Game::rps().throws_for(theirs.index(), outcome)[0])
+}
+
 impl Match {
-    fn from_str(s: &str) -> Self {
-        let fields: Vec<&str> = s.split(" ").collect();
-
-        let theirs = match fields[0] {
-            "A" => Throw::Rock,
-            "B" => Throw::Paper,
-            "C" => Throw::Scissors,
-            _ => panic!("unknown opponent throw"),
-        };
+    fn calculate_score(&self, scoring: &ScoringTable) -> i32 {
+        scoring.score(Game::rps(), self.mine.index(), self.theirs.index())
+    }
 
-        let outcome = match fields[1] {
-            "X" => Outcome::Lose,
-            "Y" => Outcome::Draw,
-            "Z" => Outcome::Win,
-            _ => panic!("unknown outcome"),
-        };
+    fn to_round(self, scoring: &ScoringTable) -> Round {
+        Round {
+            theirs: self.theirs.index(),
+            mine: self.mine.index(),
+            outcome: Game::rps().outcome(self.mine.index(), self.theirs.index()),
+            points: self.calculate_score(scoring),
+        }
+    }
+}
 
-        let mine = get_throw(theirs, outcome);
+/// Parses the opponent's column of the strategy guide, in the puzzle's encoding.
+impl FromStr for Throw {
+    type Err = Error;
 
-        Match {
-            theirs: theirs,
-            mine: mine,
-        }
+    fn from_str(s: &str) -> Result<Self> {
+        Encoding::standard().their_throw(s)
+    }
+}
+
+impl fmt::Display for Throw {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letter = Encoding::standard()
+            .their_letter(*self)
+            .expect("the puzzle's encoding has a letter for every throw");
+        write!(f, "{}", letter)
+    }
+}
+
+/// Parses our column of the strategy guide read as the outcome to achieve, in the puzzle's encoding.
+impl FromStr for Outcome {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Encoding::standard().outcome(s)
     }
+}
 
-    fn calculate_score(&self) -> i32 {
-        match self.mine {
-            Throw::Rock => {
-                let throw_score = 1;
-                throw_score
-                    + match self.theirs {
-                        Throw::Rock => 3,
-                        Throw::Paper => 0,
-                        Throw::Scissors => 6,
-                    }
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letter = Encoding::standard()
+            .outcome_letter(*self)
+            .expect("the puzzle's encoding has a letter for every outcome");
+        write!(f, "{}", letter)
+    }
+}
+
+/// Parses one strategy guide line, e.g. `A Y`, in the puzzle's encoding with the second column read as the outcome.
+impl FromStr for Match {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Encoding::standard().parse_match(s, Mode::Outcomes)
+    }
+}
+
+/// Writes the strategy guide line that FromStr reads back into this Match.
+impl fmt::Display for Match {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line = Encoding::standard()
+            .encode_match(self, Mode::Outcomes)
+            .expect("the puzzle's encoding has letters for every match");
+        write!(f, "{}", line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_fromstr() -> Result<()> {
+        assert_eq!(
+            "A Y".parse::<Match>()?,
+            Match {
+                theirs: Throw::Rock,
+                mine: Throw::Rock
             }
-            Throw::Paper => {
-                let throw_score = 2;
-                throw_score
-                    + match self.theirs {
-                        Throw::Rock => 6,
-                        Throw::Paper => 3,
-                        Throw::Scissors => 0,
-                    }
+        );
+        assert_eq!(
+            "C Z".parse::<Match>()?,
+            Match {
+                theirs: Throw::Scissors,
+                mine: Throw::Rock
             }
-            Throw::Scissors => {
-                let throw_score = 3;
-                throw_score
-                    + match self.theirs {
-                        Throw::Rock => 0,
-                        Throw::Paper => 6,
-                        Throw::Scissors => 3,
-                    }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_fromstr_fails() {
+        assert!("A".parse::<Match>().is_err());
+        assert!("X A".parse::<Match>().is_err());
+        assert!("A Y Z".parse::<Match>().is_err());
+    }
+
+    #[test]
+    fn test_match_display_round_trips() -> Result<()> {
+        for theirs in [Throw::Rock, Throw::Paper, Throw::Scissors] {
+            for mine in [Throw::Rock, Throw::Paper, Throw::Scissors] {
+                let m = Match { theirs, mine };
+                assert_eq!(m.to_string().parse::<Match>()?, m);
+                assert_eq!(theirs.to_string().parse::<Throw>()?, theirs);
             }
         }
+        for outcome in [Outcome::Win, Outcome::Lose, Outcome::Draw] {
+            assert_eq!(outcome.to_string().parse::<Outcome>()?, outcome);
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,208 @@
+use crate::game::Game;
+use crate::scoring::ScoringTable;
+use crate::Outcome;
+use anyhow::{anyhow, Error, Result};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReportFormat {
+    Table,
+    Csv,
+}
+
+impl FromStr for ReportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "table" => Ok(ReportFormat::Table),
+            "csv" => Ok(ReportFormat::Csv),
+            _ => Err(anyhow!("unknown report format '{}'", s)),
+        }
+    }
+}
+
+/// One scored round of a strategy guide.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Round {
+    pub theirs: usize,
+    pub mine: usize,
+    pub outcome: Outcome,
+    pub points: i32,
+}
+
+impl Round {
+    pub fn new(game: &Game, scoring: &ScoringTable, mine: usize, theirs: usize) -> Self {
+        Self {
+            theirs,
+            mine,
+            outcome: game.outcome(mine, theirs),
+            points: scoring.score(game, mine, theirs),
+        }
+    }
+}
+
+/// A round-by-round breakdown of a strategy guide.
+pub struct Report<'a> {
+    game: &'a Game,
+    rounds: Vec<Round>,
+}
+
+impl<'a> Report<'a> {
+    pub fn new(game: &'a Game, rounds: Vec<Round>) -> Self {
+        Self { game, rounds }
+    }
+
+    pub fn total(&self) -> i32 {
+        self.rounds.iter().map(|round| round.points).sum()
+    }
+
+    /// Returns how many rounds ended in each outcome and the points they earned.
+    pub fn outcome_totals(&self) -> Vec<(Outcome, usize, i32)> {
+        [Outcome::Win, Outcome::Draw, Outcome::Lose]
+            .into_iter()
+            .map(|outcome| {
+                let rounds = self.rounds.iter().filter(|round| round.outcome == outcome);
+                (
+                    outcome,
+                    rounds.clone().count(),
+                    rounds.map(|round| round.points).sum(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns how many times we made each throw, indexed like the game's throws.
+    pub fn throw_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.game.len()];
+        for round in &self.rounds {
+            counts[round.mine] += 1;
+        }
+        counts
+    }
+
+    /// Returns one CSV row per round, with a header.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("round,theirs,mine,outcome,points,running\n");
+        let mut running = 0;
+        for (idx, round) in self.rounds.iter().enumerate() {
+            running += round.points;
+            out += &format!(
+                "{},{},{},{:?},{},{}\n",
+                idx + 1,
+                self.game.name(round.theirs),
+                self.game.name(round.mine),
+                round.outcome,
+                round.points,
+                running
+            );
+        }
+        out
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Table => self.to_string(),
+            ReportFormat::Csv => self.to_csv(),
+        }
+    }
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = (0..self.game.len())
+            .map(|throw| self.game.name(throw).len())
+            .max()
+            .unwrap_or(0)
+            .max("theirs".len());
+
+        writeln!(
+            f,
+            "{:>5}  {:<width$}  {:<width$}  {:<7}  {:>6}  {:>7}",
+            "round", "theirs", "mine", "outcome", "points", "running"
+        )?;
+        let mut running = 0;
+        for (idx, round) in self.rounds.iter().enumerate() {
+            running += round.points;
+            writeln!(
+                f,
+                "{:>5}  {:<width$}  {:<width$}  {:<7}  {:>6}  {:>7}",
+                idx + 1,
+                self.game.name(round.theirs),
+                self.game.name(round.mine),
+                format!("{:?}", round.outcome),
+                round.points,
+                running
+            )?;
+        }
+
+        writeln!(f)?;
+        for (outcome, count, points) in self.outcome_totals() {
+            writeln!(
+                f,
+                "{:<8} {:>5} rounds {:>7} points",
+                format!("{:?}", outcome),
+                count,
+                points
+            )?;
+        }
+        for (throw, count) in self.throw_counts().into_iter().enumerate() {
+            writeln!(f, "{:<8} {:>5} rounds", self.game.name(throw), count)?;
+        }
+        write!(f, "Total: {}", self.total())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Report<'static> {
+        let game = Game::rps();
+        let scoring = ScoringTable::standard(game);
+        // The puzzle's example read as outcomes: Rock/Rock, Rock/Paper, Rock/Scissors
+        Report::new(
+            game,
+            vec![
+                Round::new(game, &scoring, 0, 0),
+                Round::new(game, &scoring, 0, 1),
+                Round::new(game, &scoring, 0, 2),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_aggregates() {
+        let report = example();
+        assert_eq!(report.total(), 12);
+        assert_eq!(
+            report.outcome_totals(),
+            [
+                (Outcome::Win, 1, 7),
+                (Outcome::Draw, 1, 4),
+                (Outcome::Lose, 1, 1)
+            ]
+        );
+        assert_eq!(report.throw_counts(), [3, 0, 0]);
+    }
+
+    #[test]
+    fn test_to_csv() {
+        assert_eq!(
+            example().to_csv(),
+            "round,theirs,mine,outcome,points,running\n\
+             1,Rock,Rock,Draw,4,4\n\
+             2,Paper,Rock,Lose,1,5\n\
+             3,Scissors,Rock,Win,7,12\n"
+        );
+    }
+
+    #[test]
+    fn test_table() {
+        let table = example().to_string();
+        assert!(table.contains("    2  Paper     Rock      Lose          1        5"));
+        assert!(table.contains("Win          1 rounds       7 points"));
+        assert!(table.ends_with("Total: 12"));
+    }
+}
@@ -0,0 +1,192 @@
+use crate::game::Game;
+use crate::Outcome;
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// The points awarded for each throw and each outcome of a round.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoringTable {
+    /// Points per throw, indexed like the game's throws.
+    throw_points: Vec<i32>,
+    win: i32,
+    draw: i32,
+    lose: i32,
+}
+
+/// The serialized form of a ScoringTable, in TOML or JSON.
+///
+/// ```toml
+/// [throws]
+/// Rock = 1
+/// Paper = 2
+/// Scissors = 3
+/// [outcomes]
+/// win = 6
+/// draw = 3
+/// lose = 0
+/// ```
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Definition {
+    throws: HashMap<String, i32>,
+    outcomes: OutcomePoints,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OutcomePoints {
+    win: i32,
+    draw: i32,
+    lose: i32,
+}
+
+impl ScoringTable {
+    /// The puzzle's scoring: each throw is worth its game score, and a loss, draw or win is worth 0, 3 or 6.
+    pub fn standard(game: &Game) -> Self {
+        Self {
+            throw_points: (0..game.len())
+                .map(|throw| game.throw_score(throw))
+                .collect(),
+            win: 6,
+            draw: 3,
+            lose: 0,
+        }
+    }
+
+    /// Loads a table for `game`, reading JSON if the file ends in `.json` and TOML otherwise.
+    pub fn load(path: &Path, game: &Game) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|why| anyhow!("failed to open {}: {}", path.display(), why))?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&content, game),
+            _ => Self::from_toml(&content, game),
+        }
+    }
+
+    pub fn from_toml(content: &str, game: &Game) -> Result<Self> {
+        Self::from_definition(toml::from_str(content)?, game)
+    }
+
+    pub fn from_json(content: &str, game: &Game) -> Result<Self> {
+        Self::from_definition(serde_json::from_str(content)?, game)
+    }
+
+    /// Resolves throw names against `game` and checks the table is usable.
+    fn from_definition(definition: Definition, game: &Game) -> Result<Self> {
+        let mut throw_points = vec![None; game.len()];
+        for (name, points) in &definition.throws {
+            throw_points[game.index_of(name)?] = Some(*points);
+        }
+        let throw_points = throw_points
+            .into_iter()
+            .enumerate()
+            .map(|(throw, points)| points.ok_or(anyhow!("no points for '{}'", game.name(throw))))
+            .collect::<Result<_>>()?;
+
+        let OutcomePoints { win, draw, lose } = definition.outcomes;
+        // Deriving a throw from a desired outcome assumes winning is never worse than losing
+        if !(win >= draw && draw >= lose) {
+            bail!(
+                "outcome points must satisfy win >= draw >= lose, got {}/{}/{}",
+                win,
+                draw,
+                lose
+            );
+        }
+
+        Ok(Self {
+            throw_points,
+            win,
+            draw,
+            lose,
+        })
+    }
+
+    pub fn throw_points(&self, throw: usize) -> i32 {
+        self.throw_points[throw]
+    }
+
+    pub fn outcome_points(&self, outcome: Outcome) -> i32 {
+        match outcome {
+            Outcome::Win => self.win,
+            Outcome::Draw => self.draw,
+            Outcome::Lose => self.lose,
+        }
+    }
+
+    /// Returns the total points for one round of `game`.
+    pub fn score(&self, game: &Game, mine: usize, theirs: usize) -> i32 {
+        self.throw_points(mine) + self.outcome_points(game.outcome(mine, theirs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard() {
+        let game = Game::rps();
+        let table = ScoringTable::standard(game);
+        // Rock against Scissors, Paper, Rock
+        assert_eq!(table.score(game, 0, 2), 7);
+        assert_eq!(table.score(game, 0, 1), 1);
+        assert_eq!(table.score(game, 0, 0), 4);
+    }
+
+    #[test]
+    fn test_from_toml_and_json() -> Result<()> {
+        let game = Game::rps();
+        let toml_table = ScoringTable::from_toml(
+            r#"
+            [throws]
+            Rock = 10
+            Paper = 20
+            Scissors = 30
+            [outcomes]
+            win = 100
+            draw = 50
+            lose = -5
+            "#,
+            game,
+        )?;
+        let json_table = ScoringTable::from_json(
+            r#"{"throws": {"Rock": 10, "Paper": 20, "Scissors": 30},
+                "outcomes": {"win": 100, "draw": 50, "lose": -5}}"#,
+            game,
+        )?;
+        assert_eq!(toml_table, json_table);
+        assert_eq!(toml_table.score(game, 1, 0), 120);
+        assert_eq!(toml_table.score(game, 2, 0), 25);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_toml_fails() {
+        let game = Game::rps();
+        let outcomes = "[outcomes]\nwin = 6\ndraw = 3\nlose = 0";
+        // Missing Scissors
+        assert!(ScoringTable::from_toml(
+            &format!("[throws]\nRock = 1\nPaper = 2\n{}", outcomes),
+            game
+        )
+        .is_err());
+        // Unknown throw
+        assert!(ScoringTable::from_toml(
+            &format!(
+                "[throws]\nRock = 1\nPaper = 2\nScissors = 3\nSpock = 4\n{}",
+                outcomes
+            ),
+            game
+        )
+        .is_err());
+        // Losing pays more than winning
+        assert!(ScoringTable::from_toml(
+            "[throws]\nRock = 1\nPaper = 2\nScissors = 3\n[outcomes]\nwin = 0\ndraw = 3\nlose = 6",
+            game
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{get_throw, Match, Outcome, Throw};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Picks our next throw from the rounds played so far.
+pub trait Strategy {
+    fn name(&self) -> &str;
+
+    /// Returns the next throw. `history` holds the earlier rounds of this match from our point of view, so `mine` is
+    /// our own throw.
+    fn next_throw(&mut self, history: &[Match]) -> Throw;
+}
+
+/// Replays a fixed list of throws, starting over when it runs out.
+pub struct FixedGuide {
+    name: String,
+    throws: Vec<Throw>,
+}
+
+impl FixedGuide {
+    pub fn new(name: &str, throws: Vec<Throw>) -> Self {
+        assert!(!throws.is_empty(), "a guide needs at least one throw");
+        Self {
+            name: String::from(name),
+            throws,
+        }
+    }
+}
+
+impl Strategy for FixedGuide {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn next_throw(&mut self, history: &[Match]) -> Throw {
+        self.throws[history.len() % self.throws.len()]
+    }
+}
+
+/// Beats the opponent's most frequent throw so far.
+pub struct FrequencyCounter;
+
+impl Strategy for FrequencyCounter {
+    fn name(&self) -> &str {
+        "frequency"
+    }
+
+    fn next_throw(&mut self, history: &[Match]) -> Throw {
+        let mut counts = [0usize; 3];
+        for round in history {
+            counts[round.theirs.index()] += 1;
+        }
+        // Ties go to the earliest throw, so an empty history predicts Rock
+        let predicted = (0..3).rev().max_by_key(|idx| counts[*idx]).unwrap_or(0);
+        get_throw(Throw::from_index(predicted), Outcome::Win)
+    }
+}
+
+/// Predicts the opponent's next throw from what they have followed their last throw with before.
+pub struct MarkovPredictor;
+
+impl Strategy for MarkovPredictor {
+    fn name(&self) -> &str {
+        "markov"
+    }
+
+    fn next_throw(&mut self, history: &[Match]) -> Throw {
+        let last = match history.last() {
+            Some(round) => round.theirs,
+            None => return FrequencyCounter.next_throw(history),
+        };
+
+        // transitions[a][b] counts how often the opponent threw b right after a
+        let mut transitions = [[0usize; 3]; 3];
+        for pair in history.windows(2) {
+            transitions[pair[0].theirs.index()][pair[1].theirs.index()] += 1;
+        }
+
+        let seen = &transitions[last.index()];
+        if seen.iter().all(|count| *count == 0) {
+            return FrequencyCounter.next_throw(history);
+        }
+        let predicted = (0..3).rev().max_by_key(|idx| seen[*idx]).unwrap_or(0);
+        get_throw(Throw::from_index(predicted), Outcome::Win)
+    }
+}
+
+/// Throws uniformly at random, reproducibly for a given seed.
+pub struct RandomThrows {
+    name: String,
+    rng: StdRng,
+}
+
+impl RandomThrows {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            name: format!("random({})", seed),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for RandomThrows {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn next_throw(&mut self, _history: &[Match]) -> Throw {
+        Throw::from_index(self.rng.gen_range(0..3))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(theirs: &[Throw]) -> Vec<Match> {
+        theirs
+            .iter()
+            .map(|theirs| Match {
+                mine: Throw::Rock,
+                theirs: *theirs,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fixed_guide() {
+        let mut guide = FixedGuide::new("guide", vec![Throw::Rock, Throw::Paper]);
+        assert_eq!(guide.next_throw(&history(&[])), Throw::Rock);
+        assert_eq!(guide.next_throw(&history(&[Throw::Rock])), Throw::Paper);
+        assert_eq!(
+            guide.next_throw(&history(&[Throw::Rock, Throw::Rock])),
+            Throw::Rock
+        );
+    }
+
+    #[test]
+    fn test_frequency_counter() {
+        let seen = history(&[Throw::Scissors, Throw::Paper, Throw::Scissors]);
+        assert_eq!(FrequencyCounter.next_throw(&seen), Throw::Rock);
+        assert_eq!(FrequencyCounter.next_throw(&[]), Throw::Paper);
+    }
+
+    #[test]
+    fn test_markov_predictor() {
+        // Rock has always been followed by Scissors
+        let seen = history(&[Throw::Rock, Throw::Scissors, Throw::Paper, Throw::Rock]);
+        assert_eq!(MarkovPredictor.next_throw(&seen), Throw::Rock);
+    }
+
+    #[test]
+    fn test_random_is_reproducible() {
+        let throws = |seed| {
+            let mut random = RandomThrows::new(seed);
+            (0..20).map(|_| random.next_throw(&[])).collect::<Vec<_>>()
+        };
+        assert_eq!(throws(7), throws(7));
+        assert_ne!(throws(7), throws(8));
+    }
+}
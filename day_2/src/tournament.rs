@@ -0,0 +1,168 @@
+use crate::scoring::ScoringTable;
+use crate::strategy::Strategy;
+use crate::Match;
+use std::fmt;
+
+/// One strategy's results across a tournament.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    /// Matches won, drawn and lost on points.
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    /// Points scored over every round played.
+    pub points: i32,
+}
+
+/// Plays every strategy against every other for `rounds` rounds and returns the standings, best first.
+///
+/// A match is won by whoever scores more points over its rounds, and the leaderboard is ordered by matches won and
+/// then by points.
+pub fn round_robin(
+    strategies: &mut [Box<dyn Strategy>],
+    rounds: usize,
+    scoring: &ScoringTable,
+) -> Vec<Standing> {
+    let mut standings: Vec<Standing> = strategies
+        .iter()
+        .map(|strategy| Standing {
+            name: String::from(strategy.name()),
+            wins: 0,
+            draws: 0,
+            losses: 0,
+            points: 0,
+        })
+        .collect();
+
+    for a in 0..strategies.len() {
+        for b in (a + 1)..strategies.len() {
+            let (left, right) = strategies.split_at_mut(b);
+            let (points_a, points_b) = play(left[a].as_mut(), right[0].as_mut(), rounds, scoring);
+
+            standings[a].points += points_a;
+            standings[b].points += points_b;
+            if points_a > points_b {
+                standings[a].wins += 1;
+                standings[b].losses += 1;
+            } else if points_a < points_b {
+                standings[a].losses += 1;
+                standings[b].wins += 1;
+            } else {
+                standings[a].draws += 1;
+                standings[b].draws += 1;
+            }
+        }
+    }
+
+    standings.sort_by(|x, y| {
+        y.wins
+            .cmp(&x.wins)
+            .then(y.points.cmp(&x.points))
+            .then(x.name.cmp(&y.name))
+    });
+    standings
+}
+
+/// Plays one match and returns both players' points.
+fn play(
+    a: &mut dyn Strategy,
+    b: &mut dyn Strategy,
+    rounds: usize,
+    scoring: &ScoringTable,
+) -> (i32, i32) {
+    // Each side sees the history from its own point of view
+    let mut history_a: Vec<Match> = Vec::with_capacity(rounds);
+    let mut history_b: Vec<Match> = Vec::with_capacity(rounds);
+    for _ in 0..rounds {
+        let throw_a = a.next_throw(&history_a);
+        let throw_b = b.next_throw(&history_b);
+        history_a.push(Match {
+            mine: throw_a,
+            theirs: throw_b,
+        });
+        history_b.push(Match {
+            mine: throw_b,
+            theirs: throw_a,
+        });
+    }
+    (
+        history_a
+            .iter()
+            .map(|round| round.calculate_score(scoring))
+            .sum(),
+        history_b
+            .iter()
+            .map(|round| round.calculate_score(scoring))
+            .sum(),
+    )
+}
+
+/// Renders standings as a leaderboard.
+pub struct Leaderboard<'a>(pub &'a [Standing]);
+
+impl fmt::Display for Leaderboard<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .0
+            .iter()
+            .map(|standing| standing.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("strategy".len());
+
+        writeln!(
+            f,
+            "{:>4}  {:<width$}  {:>3}  {:>3}  {:>3}  {:>8}",
+            "rank", "strategy", "W", "D", "L", "points"
+        )?;
+        for (idx, standing) in self.0.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>4}  {:<width$}  {:>3}  {:>3}  {:>3}  {:>8}",
+                idx + 1,
+                standing.name,
+                standing.wins,
+                standing.draws,
+                standing.losses,
+                standing.points
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::strategy::{FixedGuide, FrequencyCounter, MarkovPredictor};
+    use crate::Throw;
+
+    #[test]
+    fn test_round_robin() {
+        let scoring = ScoringTable::standard(Game::rps());
+        let mut strategies: Vec<Box<dyn Strategy>> = vec![
+            Box::new(FixedGuide::new("rock", vec![Throw::Rock])),
+            Box::new(FixedGuide::new("paper", vec![Throw::Paper])),
+            Box::new(FrequencyCounter),
+        ];
+        let standings = round_robin(&mut strategies, 10, &scoring);
+
+        let names: Vec<_> = standings.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["frequency", "paper", "rock"]);
+        // Paper always beats Rock: 10 rounds of 8 against 10 rounds of 1
+        let rock = &standings[2];
+        assert_eq!((rock.wins, rock.draws, rock.losses), (0, 0, 2));
+        for standing in &standings {
+            assert_eq!(standing.wins + standing.draws + standing.losses, 2);
+        }
+    }
+
+    #[test]
+    fn test_play_is_symmetric() {
+        let scoring = ScoringTable::standard(Game::rps());
+        let (a, b) = play(&mut MarkovPredictor, &mut MarkovPredictor, 50, &scoring);
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::items::ItemSet;
+use anyhow::{anyhow, bail, Result};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Assigns a priority to every symbol that may appear in a rucksack.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alphabet {
+    priorities: HashMap<char, u32>,
+    items: HashMap<u32, char>,
+}
+
+impl Alphabet {
+    /// The puzzle's alphabet.
+    ///
+    /// * Items 'a' - 'z' are priorities 1-26.
+    /// * Items 'A' - 'Z' are priorities 27-52.
+    pub fn standard() -> &'static Self {
+        static STANDARD: OnceLock<Alphabet> = OnceLock::new();
+        STANDARD.get_or_init(|| {
+            Self::from_pairs(('a'..='z').chain('A'..='Z').zip(1..))
+                .expect("the standard alphabet is valid")
+        })
+    }
+
+    /// Builds an alphabet from `(symbol, priority)` pairs, checking that both are unique and priorities fit in an
+    /// ItemSet.
+    pub fn from_pairs(pairs: impl IntoIterator<Item = (char, u32)>) -> Result<Self> {
+        let mut alphabet = Self {
+            priorities: HashMap::new(),
+            items: HashMap::new(),
+        };
+        for (symbol, priority) in pairs {
+            if !(1..=ItemSet::MAX_PRIORITY).contains(&priority) {
+                bail!(
+                    "priority {} of '{}' must be between 1 and {}",
+                    priority,
+                    symbol,
+                    ItemSet::MAX_PRIORITY
+                );
+            }
+            if alphabet.priorities.insert(symbol, priority).is_some() {
+                bail!("symbol '{}' is listed twice", symbol);
+            }
+            if let Some(other) = alphabet.items.insert(priority, symbol) {
+                bail!("'{}' and '{}' share priority {}", other, symbol, priority);
+            }
+        }
+        Ok(alphabet)
+    }
+
+    /// Parses a priority table with one `<symbol> <priority>` pair per line, e.g. `a 1`.
+    pub fn from_table(content: &str) -> Result<Self> {
+        let pairs = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                let mut chars = line.chars();
+                let symbol = chars
+                    .next()
+                    .ok_or(anyhow!("line {}: missing symbol", idx + 1))?;
+                let priority = chars
+                    .as_str()
+                    .trim()
+                    .parse::<u32>()
+                    .map_err(|why| anyhow!("line {}: invalid priority: {}", idx + 1, why))?;
+                Ok((symbol, priority))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_pairs(pairs)
+    }
+
+    pub fn priority(&self, symbol: char) -> Option<u32> {
+        self.priorities.get(&symbol).copied()
+    }
+
+    pub fn item(&self, priority: u32) -> Option<char> {
+        self.items.get(&priority).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard() {
+        let alphabet = Alphabet::standard();
+        assert_eq!(alphabet.priority('a'), Some(1));
+        assert_eq!(alphabet.priority('Z'), Some(52));
+        assert_eq!(alphabet.priority('1'), None);
+        assert_eq!(alphabet.item(27), Some('A'));
+    }
+
+    #[test]
+    fn test_from_table() -> Result<()> {
+        let alphabet = Alphabet::from_table("a 1\n0 53\n- 200\n  5\n")?;
+        assert_eq!(alphabet.priority('0'), Some(53));
+        assert_eq!(alphabet.priority('-'), Some(200));
+        // The symbol is the first character, even a space
+        assert_eq!(alphabet.priority(' '), Some(5));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_table_fails() {
+        assert!(Alphabet::from_table("a 1\na 2").is_err());
+        assert!(Alphabet::from_table("a 1\nb 1").is_err());
+        assert!(Alphabet::from_table("a 0").is_err());
+        assert!(Alphabet::from_table("a 256").is_err());
+        assert!(Alphabet::from_table("a x").is_err());
+    }
+}
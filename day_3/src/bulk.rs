@@ -0,0 +1,181 @@
+use crate::alphabet::Alphabet;
+use crate::items::ItemSet;
+use crate::{priority_sum, Mode};
+use anyhow::Result;
+
+/// Looks up the item bit of every byte, for alphabets of ASCII symbols with priorities below 64.
+///
+/// Bit 0 is never a priority, so it marks bytes that aren't in the alphabet.
+pub struct ByteTable {
+    bits: [u64; 256],
+}
+
+const UNKNOWN: u64 = 1;
+
+impl ByteTable {
+    /// Returns None if the alphabet doesn't fit in a byte table.
+    pub fn new(alphabet: &Alphabet) -> Option<Self> {
+        let mut bits = [UNKNOWN; 256];
+        for priority in 1..64 {
+            if let Some(symbol) = alphabet.item(priority) {
+                // Other symbols take several bytes in UTF-8
+                if !symbol.is_ascii() {
+                    return None;
+                }
+                bits[symbol as usize] = 1 << priority;
+            }
+        }
+        // Anything left out above must be unknown to the alphabet, or it would be silently dropped
+        (64..=ItemSet::MAX_PRIORITY)
+            .all(|priority| alphabet.item(priority).is_none())
+            .then_some(Self { bits })
+    }
+
+    /// Returns the items of a compartment as one bit per priority.
+    fn items(&self, compartment: &[u8]) -> u64 {
+        compartment
+            .iter()
+            .fold(0, |acc, byte| acc | self.bits[*byte as usize])
+    }
+}
+
+/// Sums the duplicate items' priorities both per rucksack and per group of `group_size`, in a single pass.
+///
+/// Works on bytes when the alphabet allows it, otherwise and for any invalid input it falls back to priority_sum(),
+/// which reports the problem the same way as usual.
+pub fn priority_sums(content: &str, group_size: usize, alphabet: &Alphabet) -> Result<(u64, u64)> {
+    let scalar = || -> Result<(u64, u64)> {
+        Ok((
+            priority_sum(content, Mode::Compartments, alphabet)?,
+            priority_sum(content, Mode::Groups(group_size), alphabet)?,
+        ))
+    };
+    let Some(table) = ByteTable::new(alphabet) else {
+        return scalar();
+    };
+    match bulk_sums(content.as_bytes(), group_size, &table) {
+        Some(sums) => Ok(sums),
+        None => scalar(),
+    }
+}
+
+/// Returns None on anything the scalar path should report.
+fn bulk_sums(content: &[u8], group_size: usize, table: &ByteTable) -> Option<(u64, u64)> {
+    let mut compartments = 0;
+    let mut groups = 0;
+    let mut group = u64::MAX;
+    let mut group_len = 0;
+
+    let lines = content
+        .split(|byte| *byte == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .filter(|line| !line.is_empty());
+    for line in lines {
+        if !line.len().is_multiple_of(2) {
+            return None;
+        }
+        let (left, right) = line.split_at(line.len() / 2);
+        let (left, right) = (table.items(left), table.items(right));
+        if (left | right) & UNKNOWN != 0 {
+            return None;
+        }
+        compartments += single_priority(left & right)?;
+
+        group &= left | right;
+        group_len += 1;
+        if group_len == group_size {
+            groups += single_priority(group)?;
+            group = u64::MAX;
+            group_len = 0;
+        }
+    }
+    (group_len == 0).then_some((compartments, groups))
+}
+
+fn single_priority(items: u64) -> Option<u64> {
+    (items.count_ones() == 1).then_some(items.trailing_zeros().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Lcg;
+
+    const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp\n\
+                           jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\n\
+                           PmmdzqPrVvPwwTWBwg\n\
+                           wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\n\
+                           ttgJtRGJQctTZtZT\n\
+                           CrZsJsPPZsGzwwsLwLmpwMDw\n";
+
+    #[test]
+    fn test_priority_sums() -> Result<()> {
+        assert_eq!(priority_sums(EXAMPLE, 3, Alphabet::standard())?, (157, 70));
+        let crlf = EXAMPLE.replace('\n', "\r\n");
+        assert_eq!(priority_sums(&crlf, 3, Alphabet::standard())?, (157, 70));
+        Ok(())
+    }
+
+    #[test]
+    fn test_priority_sums_matches_scalar() -> Result<()> {
+        // Random rucksacks, keeping only the ones and groups the puzzle considers valid
+        let mut random = Lcg::new(7);
+        let alphabet = Alphabet::standard();
+        let mut content = String::new();
+        let mut group = Vec::new();
+        while content.len() < 20_000 {
+            let line: String = (0..2 * (random.below(12) + 1))
+                .map(|_| alphabet.item(random.below(52) + 1).unwrap())
+                .collect();
+            let (left, right) = line.split_at(line.len() / 2);
+            let (left, right): (ItemSet, ItemSet) = (left.parse()?, right.parse()?);
+            if left.intersection(&right).len() != 1 {
+                continue;
+            }
+            group.push(line);
+            if group.len() == 3 {
+                let common = group
+                    .iter()
+                    .map(|line| line.parse::<ItemSet>().unwrap())
+                    .reduce(|acc, each| acc.intersection(&each))
+                    .unwrap();
+                if common.len() == 1 {
+                    content += &(group.join("\n") + "\n");
+                }
+                group.clear();
+            }
+        }
+
+        let table = ByteTable::new(alphabet).unwrap();
+        assert_eq!(
+            bulk_sums(content.as_bytes(), 3, &table),
+            Some((
+                priority_sum(&content, Mode::Compartments, alphabet)?,
+                priority_sum(&content, Mode::Groups(3), alphabet)?
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_priority_sums_fall_back() -> Result<()> {
+        let why = priority_sums("vJrwpWtwJgWrhcsFMMfFFhF1\n", 1, Alphabet::standard())
+            .unwrap_err()
+            .to_string();
+        assert_eq!(why, "line 1, column 24: unknown item '1'");
+        assert!(priority_sums("abcab\n", 1, Alphabet::standard()).is_err());
+        let trailing = format!("{}vJrwpWtwJgWrhcsFMMfFFhFp\n", EXAMPLE);
+        assert!(priority_sums(&trailing, 3, Alphabet::standard()).is_err());
+
+        // Priorities past 63 don't fit in a byte table
+        let alphabet = Alphabet::from_table("a 1\nb 100\n")?;
+        assert!(ByteTable::new(&alphabet).is_none());
+        assert_eq!(priority_sums("bbbb\n", 1, &alphabet)?, (100, 100));
+
+        // Neither do symbols outside ASCII, even if they fit in a byte
+        let alphabet = Alphabet::from_table("a 1\né 2\n")?;
+        assert!(ByteTable::new(&alphabet).is_none());
+        assert_eq!(priority_sums("éé\n", 1, &alphabet)?, (2, 2));
+        Ok(())
+    }
+}
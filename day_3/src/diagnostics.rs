@@ -0,0 +1,375 @@
+use crate::alphabet::Alphabet;
+use crate::items::ItemSet;
+use crate::{rucksack_lines, split_compartments, Mode};
+use std::fmt;
+
+/// Something wrong with a rucksack or a group of rucksacks.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Anomaly {
+    /// An item that isn't part of the alphabet.
+    UnknownItem {
+        line: usize,
+        column: usize,
+        item: char,
+    },
+    /// A rucksack that can't be split into two compartments of the same size.
+    ///
+    /// Only matters when comparing compartments, badges are found across whole rucksacks.
+    OddLength { line: usize, items: usize },
+    /// Items found more than once in the same compartment (1 or 2).
+    ///
+    /// Unusual, but it doesn't prevent finding the duplicate item so the group is still counted.
+    RepeatedItems {
+        line: usize,
+        compartment: usize,
+        items: Vec<char>,
+    },
+    /// A group, starting at `line`, whose rucksacks share no item.
+    NoCommonItem { line: usize },
+    /// A group, starting at `line`, whose rucksacks share more than one item.
+    MultipleCommonItems { line: usize, items: Vec<char> },
+    /// A group at the end of the input that is missing rucksacks.
+    PartialGroup {
+        line: usize,
+        size: usize,
+        expected: usize,
+    },
+}
+
+impl Anomaly {
+    /// Returns true if the group the anomaly was found in is left out of the priority sum in `mode`.
+    ///
+    /// This matches what priority_sum() rejects, so diagnosing never changes the total.
+    pub fn invalidates_group(&self, mode: Mode) -> bool {
+        match self {
+            Anomaly::RepeatedItems { .. } => false,
+            Anomaly::OddLength { .. } => mode == Mode::Compartments,
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let quoted = |items: &[char]| {
+            items
+                .iter()
+                .map(|item| format!("'{}'", item))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            Anomaly::UnknownItem { line, column, item } => {
+                write!(
+                    f,
+                    "line {}, column {}: unknown item '{}'",
+                    line, column, item
+                )
+            }
+            Anomaly::OddLength { line, items } => {
+                write!(f, "line {}: cannot split {} items evenly", line, items)
+            }
+            Anomaly::RepeatedItems {
+                line,
+                compartment,
+                items,
+            } => write!(
+                f,
+                "line {}: compartment {} repeats {}",
+                line,
+                compartment,
+                quoted(items)
+            ),
+            Anomaly::NoCommonItem { line } => write!(f, "line {}: no common item", line),
+            Anomaly::MultipleCommonItems { line, items } => {
+                write!(f, "line {}: multiple common items {}", line, quoted(items))
+            }
+            Anomaly::PartialGroup {
+                line,
+                size,
+                expected,
+            } => write!(
+                f,
+                "line {}: trailing group has {} rucksacks, expected {}",
+                line, size, expected
+            ),
+        }
+    }
+}
+
+/// The result of checking every group of an input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnosis {
+    /// Every anomaly found, in input order.
+    pub anomalies: Vec<Anomaly>,
+    /// Sum of the duplicate items' priorities over the valid groups only.
+    pub priority_sum: u64,
+    pub valid_groups: usize,
+    pub total_groups: usize,
+}
+
+/// Checks every rucksack and group of the input, like priority_sum() but without stopping at the first problem.
+pub fn diagnose(content: &str, mode: Mode, alphabet: &Alphabet) -> Diagnosis {
+    let lines = rucksack_lines(content);
+    let mut diagnosis = Diagnosis {
+        anomalies: Vec::new(),
+        priority_sum: 0,
+        valid_groups: 0,
+        total_groups: 0,
+    };
+
+    let groups: Vec<&[(usize, &str)]> = match mode {
+        Mode::Compartments => lines.chunks(1).collect(),
+        Mode::Groups(size) => lines.chunks(size).collect(),
+    };
+    for group in groups {
+        let line_number = group[0].0;
+        let first_anomaly = diagnosis.anomalies.len();
+        diagnosis.total_groups += 1;
+
+        if let Mode::Groups(size) = mode {
+            if group.len() != size {
+                diagnosis.anomalies.push(Anomaly::PartialGroup {
+                    line: line_number,
+                    size: group.len(),
+                    expected: size,
+                });
+                continue;
+            }
+        }
+
+        let rucksacks: Vec<(ItemSet, Option<[ItemSet; 2]>)> = group
+            .iter()
+            .map(|(line_number, line)| {
+                inspect_rucksack(alphabet, *line_number, line, &mut diagnosis.anomalies)
+            })
+            .collect();
+        if diagnosis.anomalies[first_anomaly..]
+            .iter()
+            .any(|anomaly| anomaly.invalidates_group(mode))
+        {
+            continue;
+        }
+
+        let containers: Vec<ItemSet> = match mode {
+            // Only rucksacks that split evenly are left, so every Option is Some
+            Mode::Compartments => rucksacks
+                .into_iter()
+                .flat_map(|(_, compartments)| compartments)
+                .flatten()
+                .collect(),
+            Mode::Groups(_) => rucksacks.into_iter().map(|(items, _)| items).collect(),
+        };
+        let common_items = containers
+            .iter()
+            .copied()
+            .reduce(|acc, each| acc.intersection(&each))
+            .unwrap_or_default();
+        match common_items.len() {
+            0 => diagnosis
+                .anomalies
+                .push(Anomaly::NoCommonItem { line: line_number }),
+            1 => {
+                diagnosis.priority_sum += u64::from(common_items.priorities().sum::<u32>());
+                diagnosis.valid_groups += 1;
+            }
+            _ => diagnosis.anomalies.push(Anomaly::MultipleCommonItems {
+                line: line_number,
+                items: common_items
+                    .priorities()
+                    .filter_map(|priority| alphabet.item(priority))
+                    .collect(),
+            }),
+        }
+    }
+    diagnosis
+}
+
+/// Records the anomalies of a single rucksack and returns its items, along with those of both its compartments if it
+/// has them.
+fn inspect_rucksack(
+    alphabet: &Alphabet,
+    line_number: usize,
+    rucksack: &str,
+    anomalies: &mut Vec<Anomaly>,
+) -> (ItemSet, Option<[ItemSet; 2]>) {
+    match split_compartments(rucksack) {
+        None => {
+            anomalies.push(Anomaly::OddLength {
+                line: line_number,
+                items: rucksack.chars().count(),
+            });
+            // Still worth reporting unknown items, even if there's no compartment to repeat them in
+            let items = scan_items(
+                alphabet,
+                rucksack,
+                line_number,
+                1,
+                &mut Vec::new(),
+                anomalies,
+            );
+            (items, None)
+        }
+        Some((left, right)) => {
+            let mut repeated = [Vec::new(), Vec::new()];
+            let left_items =
+                scan_items(alphabet, left, line_number, 1, &mut repeated[0], anomalies);
+            let right_column = left.chars().count() + 1;
+            let right_items = scan_items(
+                alphabet,
+                right,
+                line_number,
+                right_column,
+                &mut repeated[1],
+                anomalies,
+            );
+            for (idx, items) in repeated.into_iter().enumerate() {
+                if !items.is_empty() {
+                    anomalies.push(Anomaly::RepeatedItems {
+                        line: line_number,
+                        compartment: idx + 1,
+                        items,
+                    });
+                }
+            }
+            (
+                left_items.union(&right_items),
+                Some([left_items, right_items]),
+            )
+        }
+    }
+}
+
+/// Returns the known items of a compartment, recording unknown ones as anomalies and collecting repeated ones.
+fn scan_items(
+    alphabet: &Alphabet,
+    compartment: &str,
+    line_number: usize,
+    first_column: usize,
+    repeated: &mut Vec<char>,
+    anomalies: &mut Vec<Anomaly>,
+) -> ItemSet {
+    let mut items = ItemSet::new();
+    for (idx, c) in compartment.chars().enumerate() {
+        match alphabet.priority(c) {
+            None => anomalies.push(Anomaly::UnknownItem {
+                line: line_number,
+                column: first_column + idx,
+                item: c,
+            }),
+            Some(priority) if items.contains_priority(priority) => {
+                if !repeated.contains(&c) {
+                    repeated.push(c);
+                }
+            }
+            Some(priority) => items.insert_priority(priority),
+        }
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::priority_sum;
+    use anyhow::Result;
+
+    #[test]
+    fn test_diagnose_valid_input() {
+        let example = "vJrwpWtwJgWrhcsFMMfFFhFp\n\
+                       jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\n\
+                       PmmdzqPrVvPwwTWBwg\n\
+                       wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\n\
+                       ttgJtRGJQctTZtZT\n\
+                       CrZsJsPPZsGzwwsLwLmpwMDw\n";
+        let diagnosis = diagnose(example, Mode::Groups(3), Alphabet::standard());
+        assert_eq!(diagnosis.priority_sum, 70);
+        assert_eq!((diagnosis.valid_groups, diagnosis.total_groups), (2, 2));
+        // The example has repeated items, which don't invalidate anything
+        assert!(!diagnosis
+            .anomalies
+            .iter()
+            .any(|anomaly| anomaly.invalidates_group(Mode::Groups(3))));
+    }
+
+    #[test]
+    fn test_diagnose_anomalies() {
+        let input = "abca\n\
+                     abcdef\n\
+                     abcab\n\
+                     ab1b\n\
+                     aabb\n";
+        let diagnosis = diagnose(input, Mode::Compartments, Alphabet::standard());
+        assert_eq!(
+            diagnosis.anomalies,
+            [
+                Anomaly::NoCommonItem { line: 2 },
+                Anomaly::OddLength { line: 3, items: 5 },
+                Anomaly::UnknownItem {
+                    line: 4,
+                    column: 3,
+                    item: '1'
+                },
+                Anomaly::RepeatedItems {
+                    line: 5,
+                    compartment: 1,
+                    items: vec!['a']
+                },
+                Anomaly::RepeatedItems {
+                    line: 5,
+                    compartment: 2,
+                    items: vec!['b']
+                },
+                Anomaly::NoCommonItem { line: 5 },
+            ]
+        );
+        // Only "abca" is valid
+        assert_eq!(diagnosis.priority_sum, 1);
+        assert_eq!((diagnosis.valid_groups, diagnosis.total_groups), (1, 5));
+    }
+
+    #[test]
+    fn test_diagnose_groups() {
+        let input = "abcd\nabce\nabcf\nxy\n";
+        let diagnosis = diagnose(input, Mode::Groups(3), Alphabet::standard());
+        assert_eq!(
+            diagnosis.anomalies,
+            [
+                Anomaly::MultipleCommonItems {
+                    line: 1,
+                    items: vec!['a', 'b', 'c']
+                },
+                Anomaly::PartialGroup {
+                    line: 4,
+                    size: 1,
+                    expected: 3
+                },
+            ]
+        );
+        assert_eq!((diagnosis.valid_groups, diagnosis.total_groups), (0, 2));
+        assert_eq!(
+            diagnosis.anomalies[0].to_string(),
+            "line 1: multiple common items 'a', 'b', 'c'"
+        );
+    }
+
+    #[test]
+    fn test_diagnose_keeps_the_total() -> Result<()> {
+        // Odd rucksacks can't be split, but that doesn't matter for their badge
+        let input = "abc\nAbC\n";
+        let diagnosis = diagnose(input, Mode::Groups(2), Alphabet::standard());
+        assert_eq!(
+            diagnosis.anomalies[0],
+            Anomaly::OddLength { line: 1, items: 3 }
+        );
+        assert_eq!(
+            diagnosis.priority_sum,
+            priority_sum(input, Mode::Groups(2), Alphabet::standard())?
+        );
+        assert_eq!((diagnosis.valid_groups, diagnosis.total_groups), (1, 1));
+
+        let diagnosis = diagnose(input, Mode::Compartments, Alphabet::standard());
+        assert_eq!((diagnosis.valid_groups, diagnosis.total_groups), (0, 2));
+        Ok(())
+    }
+}
@@ -0,0 +1,279 @@
+use crate::items::ItemSet;
+
+/// Partitions rucksacks, in any order, into groups of `size` that share exactly one item.
+///
+/// Returns the indices of each group's rucksacks, or None if no such partition exists. The search backtracks, always
+/// continuing with the rucksack that has the fewest possible partners left so that dead ends are found early.
+pub fn find_groups(rucksacks: &[ItemSet], size: usize) -> Option<Vec<Vec<usize>>> {
+    if size == 0 || !rucksacks.len().is_multiple_of(size) {
+        return None;
+    }
+    Solver::new(rucksacks, size).solve()
+}
+
+/// One group being tried: the rucksack it was started from and which of its candidate partners are in it.
+struct Frame {
+    pick: usize,
+    candidates: Vec<usize>,
+    /// Positions in `candidates`, increasing.
+    chosen: Vec<usize>,
+    started: bool,
+}
+
+impl Frame {
+    fn group(&self) -> Vec<usize> {
+        std::iter::once(self.pick)
+            .chain(self.chosen.iter().map(|k| self.candidates[*k]))
+            .collect()
+    }
+}
+
+struct Solver<'a> {
+    rucksacks: &'a [ItemSet],
+    size: usize,
+    /// Words per row of the bit matrices below.
+    words: usize,
+    /// Row `i` has bit `j` set when rucksacks `i` and `j` share at least one item.
+    partners: Vec<u64>,
+    unassigned: Vec<u64>,
+}
+
+impl<'a> Solver<'a> {
+    fn new(rucksacks: &'a [ItemSet], size: usize) -> Self {
+        let count = rucksacks.len();
+        let words = count.div_ceil(64);
+        let mut partners = vec![0; count * words];
+        for i in 0..count {
+            for j in (i + 1)..count {
+                if !rucksacks[i].intersection(&rucksacks[j]).is_empty() {
+                    partners[i * words + j / 64] |= 1 << (j % 64);
+                    partners[j * words + i / 64] |= 1 << (i % 64);
+                }
+            }
+        }
+        let mut unassigned = vec![0; words];
+        for i in 0..count {
+            unassigned[i / 64] |= 1 << (i % 64);
+        }
+        Self {
+            rucksacks,
+            size,
+            words,
+            partners,
+            unassigned,
+        }
+    }
+
+    fn assign(&mut self, idx: usize) {
+        self.unassigned[idx / 64] &= !(1 << (idx % 64));
+    }
+
+    fn unassign(&mut self, idx: usize) {
+        self.unassigned[idx / 64] |= 1 << (idx % 64);
+    }
+
+    /// Returns the unassigned partners of a rucksack.
+    fn open_partners(&self, idx: usize) -> impl Iterator<Item = u64> + '_ {
+        self.partners[idx * self.words..(idx + 1) * self.words]
+            .iter()
+            .zip(&self.unassigned)
+            .map(|(row, open)| row & open)
+    }
+
+    /// Backtracks over one frame per group, so deep searches don't need a deep call stack.
+    fn solve(&mut self) -> Option<Vec<Vec<usize>>> {
+        let mut frames: Vec<Frame> = Vec::new();
+        loop {
+            // The most constrained rucksack goes first
+            let pick = bit_indices(&self.unassigned)
+                .map(|idx| {
+                    let options: u32 = self.open_partners(idx).map(u64::count_ones).sum();
+                    (idx, options as usize)
+                })
+                .min_by_key(|(_, options)| *options);
+            let Some((pick, options)) = pick else {
+                // Everything is grouped
+                return Some(frames.iter().map(Frame::group).collect());
+            };
+
+            if options + 1 >= self.size {
+                let mut frame = Frame {
+                    pick,
+                    candidates: bit_indices(&self.open_partners(pick).collect::<Vec<_>>())
+                        .collect(),
+                    chosen: Vec::new(),
+                    started: false,
+                };
+                self.assign(pick);
+                if self.advance(&mut frame) {
+                    frames.push(frame);
+                    continue;
+                }
+                self.unassign(pick);
+            }
+
+            // Dead end, try the next group of the most recent frame that has one
+            loop {
+                let mut frame = frames.pop()?;
+                if self.advance(&mut frame) {
+                    frames.push(frame);
+                    break;
+                }
+                self.unassign(frame.pick);
+            }
+        }
+    }
+
+    /// Moves a frame on to its next group sharing exactly one item, returning false once there are none left.
+    fn advance(&mut self, frame: &mut Frame) -> bool {
+        let need = self.size - 1;
+        for k in &frame.chosen {
+            self.unassign(frame.candidates[*k]);
+        }
+
+        let mut start = 0;
+        if frame.started {
+            match frame.chosen.pop() {
+                Some(k) => start = k + 1,
+                None => return false,
+            }
+        }
+        frame.started = true;
+
+        loop {
+            let common = frame
+                .chosen
+                .iter()
+                .fold(self.rucksacks[frame.pick], |acc, k| {
+                    acc.intersection(&self.rucksacks[frame.candidates[*k]])
+                });
+            if frame.chosen.len() == need {
+                if common.len() == 1 {
+                    break;
+                }
+                match frame.chosen.pop() {
+                    Some(k) => start = k + 1,
+                    None => return false,
+                }
+                continue;
+            }
+
+            // Leave enough candidates to fill the rest of the group
+            let last = frame.candidates.len() + frame.chosen.len() + 1;
+            let found = (start..last.saturating_sub(need)).find(|k| {
+                !common
+                    .intersection(&self.rucksacks[frame.candidates[*k]])
+                    .is_empty()
+            });
+            match found {
+                Some(k) => {
+                    frame.chosen.push(k);
+                    start = k + 1;
+                }
+                None => match frame.chosen.pop() {
+                    Some(k) => start = k + 1,
+                    None => return false,
+                },
+            }
+        }
+
+        for k in &frame.chosen {
+            self.assign(frame.candidates[*k]);
+        }
+        true
+    }
+}
+
+/// Iterates over the indices of the set bits of a bit row, lowest first.
+fn bit_indices(row: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    row.iter().enumerate().flat_map(|(word, bits)| {
+        (0..64)
+            .filter(move |bit| bits & (1 << bit) != 0)
+            .map(move |bit| word * 64 + bit)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Lcg;
+
+    // The puzzle's example, shuffled
+    const SHUFFLED: [&str; 6] = [
+        "ttgJtRGJQctTZtZT",
+        "vJrwpWtwJgWrhcsFMMfFFhFp",
+        "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn",
+        "PmmdzqPrVvPwwTWBwg",
+        "CrZsJsPPZsGzwwsLwLmpwMDw",
+        "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
+    ];
+
+    fn check_partition(rucksacks: &[ItemSet], groups: &[Vec<usize>], size: usize) {
+        let mut seen = vec![false; rucksacks.len()];
+        for group in groups {
+            assert_eq!(group.len(), size);
+            let common = group
+                .iter()
+                .map(|idx| rucksacks[*idx])
+                .reduce(|acc, each| acc.intersection(&each))
+                .unwrap();
+            assert_eq!(common.len(), 1);
+            for idx in group {
+                assert!(!seen[*idx]);
+                seen[*idx] = true;
+            }
+        }
+        assert!(seen.iter().all(|seen| *seen));
+    }
+
+    #[test]
+    fn test_find_groups() {
+        let rucksacks: Vec<ItemSet> = SHUFFLED.iter().map(|s| s.parse().unwrap()).collect();
+        let groups = find_groups(&rucksacks, 3).unwrap();
+        check_partition(&rucksacks, &groups, 3);
+    }
+
+    #[test]
+    fn test_find_groups_none() {
+        let rucksacks: Vec<ItemSet> = ["ab", "cd", "ab", "cd"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        // Pairs of the same rucksack share two items, mixed pairs share none
+        assert_eq!(find_groups(&rucksacks, 2), None);
+        // Doesn't divide evenly
+        assert_eq!(find_groups(&rucksacks, 3), None);
+        assert_eq!(find_groups(&[], 3), Some(vec![]));
+    }
+
+    #[test]
+    fn test_find_groups_many() {
+        // Random rucksacks with a planted partition, shuffled
+        let mut random = Lcg::new(3);
+        let mut rucksacks = Vec::new();
+        for _ in 0..1000 {
+            let badge = random.below(52) + 1;
+            let mut group = [ItemSet::new(); 3];
+            for rucksack in group.iter_mut() {
+                rucksack.insert_priority(badge);
+                for _ in 0..16 {
+                    rucksack.insert_priority(random.below(52) + 1);
+                }
+            }
+            // Only the badge may be shared by all three
+            let others = group[0].intersection(&group[1]).intersection(&group[2]);
+            for priority in others.priorities().filter(|priority| *priority != badge) {
+                let mut single = ItemSet::new();
+                single.insert_priority(priority);
+                group[2] = group[2].difference(&single);
+            }
+            rucksacks.extend(group);
+        }
+        for i in (1..rucksacks.len()).rev() {
+            rucksacks.swap(i, random.below(i as u32 + 1) as usize);
+        }
+
+        let groups = find_groups(&rucksacks, 3).unwrap();
+        check_partition(&rucksacks, &groups, 3);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::alphabet::Alphabet;
+use anyhow::{anyhow, Error, Result};
+use std::fmt;
+use std::str::FromStr;
+
+const WORDS: usize = 4;
+
+/// A set of rucksack items, stored as one bit per item priority.
+///
+/// Priorities range from 1 to `ItemSet::MAX_PRIORITY`, what each one means is up to an Alphabet. FromStr, Display
+/// and iter() use the puzzle's standard alphabet.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ItemSet([u64; WORDS]);
+
+impl ItemSet {
+    pub const MAX_PRIORITY: u32 = (WORDS * 64 - 1) as u32;
+
+    pub fn new() -> Self {
+        Self([0; WORDS])
+    }
+
+    /// Adds an item by priority.
+    ///
+    /// Panics if the priority is out of range.
+    pub fn insert_priority(&mut self, priority: u32) {
+        assert!(
+            (1..=Self::MAX_PRIORITY).contains(&priority),
+            "priority {} out of range",
+            priority
+        );
+        self.0[priority as usize / 64] |= 1 << (priority % 64);
+    }
+
+    /// Adds an item of the standard alphabet, failing if it has no priority.
+    pub fn insert(&mut self, item: char) -> Result<()> {
+        let priority = Alphabet::standard()
+            .priority(item)
+            .ok_or(anyhow!("unhandled item '{}'", item))?;
+        self.insert_priority(priority);
+        Ok(())
+    }
+
+    pub fn contains_priority(&self, priority: u32) -> bool {
+        priority <= Self::MAX_PRIORITY
+            && self.0[priority as usize / 64] & (1 << (priority % 64)) != 0
+    }
+
+    /// Returns true if the set has an item of the standard alphabet.
+    // The solvers work on priorities, this is the letter based counterpart of contains_priority()
+    #[allow(dead_code)]
+    pub fn contains(&self, item: char) -> bool {
+        Alphabet::standard()
+            .priority(item)
+            .is_some_and(|priority| self.contains_priority(priority))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] | other.0[i]))
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] & other.0[i]))
+    }
+
+    /// Returns the items of this set that aren't in `other`.
+    // Finding duplicates only needs intersections, difference completes the set operations
+    #[allow(dead_code)]
+    pub fn difference(&self, other: &Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] & !other.0[i]))
+    }
+
+    /// Returns the raw bits, where bit `n` of the whole array is the item with priority `n`.
+    #[cfg(test)]
+    pub fn bits(&self) -> [u64; WORDS] {
+        self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|word| *word == 0)
+    }
+
+    /// Iterates over the priorities in the set, lowest first.
+    pub fn priorities(&self) -> Priorities {
+        Priorities {
+            words: self.0,
+            word: 0,
+        }
+    }
+
+    /// Iterates over the standard alphabet's items and their priorities, lowest priority first.
+    pub fn iter(&self) -> impl Iterator<Item = (char, u32)> {
+        self.priorities().filter_map(|priority| {
+            Alphabet::standard()
+                .item(priority)
+                .map(|item| (item, priority))
+        })
+    }
+}
+
+pub struct Priorities {
+    words: [u64; WORDS],
+    word: usize,
+}
+
+impl Iterator for Priorities {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.word < WORDS {
+            let bits = &mut self.words[self.word];
+            if *bits != 0 {
+                let priority = (self.word * 64) as u32 + bits.trailing_zeros();
+                // Clear the lowest set bit
+                *bits &= *bits - 1;
+                return Some(priority);
+            }
+            self.word += 1;
+        }
+        None
+    }
+}
+
+/// Parses the items of a rucksack or compartment in the standard alphabet.
+impl FromStr for ItemSet {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut set = Self::new();
+        for c in s.chars() {
+            set.insert(c)?;
+        }
+        Ok(set)
+    }
+}
+
+/// Shows the items as letters of the standard alphabet, lowest priority first.
+impl fmt::Display for ItemSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.iter().try_for_each(|(item, _)| write!(f, "{}", item))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fromstr_and_display() -> Result<()> {
+        assert_eq!("cabBa".parse::<ItemSet>()?.to_string(), "abcB");
+        assert_eq!("".parse::<ItemSet>()?, ItemSet::new());
+        assert!("ab1".parse::<ItemSet>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_operations() -> Result<()> {
+        let left: ItemSet = "abcX".parse()?;
+        let right: ItemSet = "cdXY".parse()?;
+        assert_eq!(left.union(&right).to_string(), "abcdXY");
+        assert_eq!(left.intersection(&right).to_string(), "cX");
+        assert_eq!(left.difference(&right).to_string(), "ab");
+        assert_eq!(left.len(), 4);
+        assert!(left.contains('X'));
+        assert!(!left.contains('Y'));
+        assert!(left.difference(&left).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_iter() -> Result<()> {
+        let items: Vec<_> = "zAa".parse::<ItemSet>()?.iter().collect();
+        assert_eq!(items, [('a', 1), ('z', 26), ('A', 27)]);
+        let all: ItemSet = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ".parse()?;
+        assert_eq!(all.len(), 52);
+        assert_eq!(all.iter().last(), Some(('Z', 52)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_wide_priorities() {
+        let mut set = ItemSet::new();
+        for priority in [1, 63, 64, 128, ItemSet::MAX_PRIORITY] {
+            set.insert_priority(priority);
+        }
+        assert_eq!(set.len(), 5);
+        assert!(set.contains_priority(64));
+        assert!(!set.contains_priority(65));
+        assert_eq!(
+            set.priorities().collect::<Vec<_>>(),
+            [1, 63, 64, 128, ItemSet::MAX_PRIORITY]
+        );
+    }
+}
@@ -1,9 +1,45 @@
-#![feature(iter_array_chunks)]
-
+use anyhow::{anyhow, bail, Error, Result};
+use std::env;
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
+
+mod alphabet;
+mod bulk;
+mod diagnostics;
+mod grouping;
+mod items;
+#[cfg(test)]
+mod testing;
+use alphabet::Alphabet;
+use bulk::priority_sums;
+use diagnostics::diagnose;
+use grouping::find_groups;
+use items::ItemSet;
+
+fn main() -> Result<()> {
+    let mut mode = Mode::Groups(3);
+    let mut alphabet = Alphabet::standard().clone();
+    let mut diagnostics = false;
+    let mut discover = false;
+    let mut bulk = false;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or(anyhow!("missing value for argument '{}'", arg))
+        };
+        match arg.as_str() {
+            "--mode" => mode = value()?.parse()?,
+            "--alphabet" => alphabet = Alphabet::from_table(&fs::read_to_string(value()?)?)?,
+            "--diagnose" => diagnostics = true,
+            "--discover" => discover = true,
+            "--bulk" => bulk = true,
+            _ => return Err(anyhow!("unknown argument '{}'", arg)),
+        }
+    }
 
-fn main() {
     let input_path = Path::new("input.txt");
 
     let content = match fs::read_to_string(input_path) {
@@ -11,71 +47,281 @@ fn main() {
         Ok(content) => content,
     };
 
-    let priority_sum: u32 = content
-        .split("\n")
-        .filter(|line| line.len() > 0)
-        .array_chunks::<3>()
-        .map(|chunk| get_duplicate_item_priority(&chunk))
-        .sum();
-    println!("Total: {}", priority_sum);
+    if bulk {
+        // Both parts at once, grouping by three unless a group size was given
+        let group_size = match mode {
+            Mode::Groups(size) => size,
+            Mode::Compartments => 3,
+        };
+        let (compartments, groups) = priority_sums(&content, group_size, &alphabet)?;
+        println!("Total (compartments): {}", compartments);
+        println!("Total (groups of {}): {}", group_size, groups);
+    } else if discover {
+        let Mode::Groups(size) = mode else {
+            bail!("--discover needs a groups mode");
+        };
+        let lines = rucksack_lines(&content);
+        let rucksacks = lines
+            .iter()
+            .map(|(line_number, line)| find_items(&alphabet, line, *line_number, 1))
+            .collect::<Result<Vec<_>>>()?;
+        let groups = find_groups(&rucksacks, size).ok_or(anyhow!(
+            "{} rucksacks cannot be split into groups of {} sharing one item",
+            rucksacks.len(),
+            size
+        ))?;
+        for group in &groups {
+            let line_numbers: Vec<_> = group.iter().map(|idx| lines[*idx].0.to_string()).collect();
+            println!("Group: lines {}", line_numbers.join(", "));
+        }
+        let containers: Vec<Vec<ItemSet>> = groups
+            .iter()
+            .map(|group| group.iter().map(|idx| rucksacks[*idx]).collect())
+            .collect();
+        println!(
+            "Total: {}",
+            containers
+                .iter()
+                .map(|group| u64::from(get_duplicate_item_priority(group)))
+                .sum::<u64>()
+        );
+    } else if diagnostics {
+        let diagnosis = diagnose(&content, mode, &alphabet);
+        for anomaly in &diagnosis.anomalies {
+            println!("{}", anomaly);
+        }
+        println!(
+            "Valid groups: {}/{}",
+            diagnosis.valid_groups, diagnosis.total_groups
+        );
+        println!("Total: {}", diagnosis.priority_sum);
+    } else {
+        println!("Total: {}", priority_sum(&content, mode, &alphabet)?);
+    }
+
+    Ok(())
+}
+
+/// How rucksacks are compared to find their duplicate item.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Mode {
+    /// Each rucksack is split into two equally sized compartments.
+    Compartments,
+    /// Consecutive rucksacks form groups of the given size.
+    Groups(usize),
+}
+
+/// Parses `compartments`, `groups` (of three) or `groups:N`.
+impl FromStr for Mode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once(':') {
+            None if s == "compartments" => Ok(Mode::Compartments),
+            None if s == "groups" => Ok(Mode::Groups(3)),
+            Some(("groups", size)) => match size.parse::<usize>()? {
+                0 => Err(anyhow!("group size must be positive")),
+                size => Ok(Mode::Groups(size)),
+            },
+            _ => Err(anyhow!("unknown mode '{}'", s)),
+        }
+    }
+}
+
+/// Sums the priorities of the duplicate items of every rucksack or group of rucksacks.
+fn priority_sum(content: &str, mode: Mode, alphabet: &Alphabet) -> Result<u64> {
+    let lines = rucksack_lines(content);
+
+    match mode {
+        Mode::Compartments => lines
+            .iter()
+            .map(|(line_number, line)| {
+                let (left, right) = split_compartments(line).ok_or(anyhow!(
+                    "line {}: cannot split {} items evenly",
+                    line_number,
+                    line.chars().count()
+                ))?;
+                Ok(u64::from(get_duplicate_item_priority(&[
+                    find_items(alphabet, left, *line_number, 1)?,
+                    find_items(alphabet, right, *line_number, left.chars().count() + 1)?,
+                ])))
+            })
+            .sum(),
+        Mode::Groups(size) => lines
+            .chunks(size)
+            .map(|group| {
+                if group.len() != size {
+                    bail!(
+                        "line {}: trailing group has {} rucksacks, expected {}",
+                        group[0].0,
+                        group.len(),
+                        size
+                    );
+                }
+                let rucksacks = group
+                    .iter()
+                    .map(|(line_number, line)| find_items(alphabet, line, *line_number, 1))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(u64::from(get_duplicate_item_priority(&rucksacks)))
+            })
+            .sum(),
+    }
+}
+
+/// Returns the non-empty lines of the input with their line numbers, to report errors against.
+fn rucksack_lines(content: &str) -> Vec<(usize, &str)> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line))
+        .filter(|(_, line)| !line.is_empty())
+        .collect()
+}
+
+/// Splits a rucksack into its two compartments, or returns None if they can't be the same size.
+fn split_compartments(rucksack: &str) -> Option<(&str, &str)> {
+    let num_items = rucksack.chars().count();
+    if !num_items.is_multiple_of(2) {
+        return None;
+    }
+    // Items may be wider than a byte, split on the middle character
+    let (middle, _) = rucksack
+        .char_indices()
+        .nth(num_items / 2)
+        .unwrap_or((rucksack.len(), ' '));
+    Some(rucksack.split_at(middle))
 }
 
 /// Returns the priority of the item found in all compartments.
 ///
 /// Panics if not exactly one duplicate is found.
-fn get_duplicate_item_priority(containers: &[&str]) -> u32 {
+fn get_duplicate_item_priority(containers: &[ItemSet]) -> u32 {
     let common_items = containers
         .iter()
-        .map(|each| find_items(each))
-        .fold(!0u64, |acc, each| acc & each);
-    if common_items != 0 {
+        .copied()
+        .reduce(|acc, each| acc.intersection(&each))
+        .unwrap_or_default();
+    match common_items.len() {
+        0 => panic!("no duplicate items found"),
+        1 => common_items.priorities().sum(),
         // Just in case, panic if there are multiple duplicates.
-        if (common_items ^ (0b1 << common_items.trailing_zeros())) != 0 {
-            panic!("multiple duplicate items found");
-        }
-        // Since a is priority 1 and represented as 0b10, trailing_zeros() equals the priority.
-        return common_items.trailing_zeros();
-    } else {
-        panic!("no duplicate items found");
+        _ => panic!("multiple duplicate items found"),
     }
 }
 
-/// Returns the items inside a compartment.
+/// Returns the items inside a compartment that starts at `first_column` of line `line_number`.
 ///
-/// Items are represented by set bits in the result.
-/// * Items 'a' - 'z' are indices 1-26.
-/// * Items 'A' - 'Z' are indices 27-52.
-fn find_items(compartment: &str) -> u64 {
-    compartment
-        .chars()
-        .map(|c| match c {
-            'a'..='z' => c as u8 - 'a' as u8 + 1u8,
-            'A'..='Z' => c as u8 - 'A' as u8 + 27u8,
-            _ => panic!("unhandled item"),
-        })
-        .fold(0u64, |acc, idx| acc | 0b1 << idx)
+/// Unknown items are reported with their line and column.
+fn find_items(
+    alphabet: &Alphabet,
+    compartment: &str,
+    line_number: usize,
+    first_column: usize,
+) -> Result<ItemSet> {
+    let mut items = ItemSet::new();
+    for (idx, c) in compartment.chars().enumerate() {
+        let priority = alphabet.priority(c).ok_or(anyhow!(
+            "line {}, column {}: unknown item '{}'",
+            line_number,
+            first_column + idx,
+            c
+        ))?;
+        items.insert_priority(priority);
+    }
+    Ok(items)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn items(compartment: &str) -> ItemSet {
+        find_items(Alphabet::standard(), compartment, 1, 1).unwrap()
+    }
+
     #[test]
     fn test_find_items() {
-        assert_eq!(find_items("abc"), 0b1110);
-        assert_eq!(find_items("aa"), 0b10);
-        assert_eq!(find_items("Aa"), 0b1 << 27 | 0b1 << 1);
+        assert_eq!(items("abc").bits()[0], 0b1110);
+        assert_eq!(items("aa").bits()[0], 0b10);
+        assert_eq!(items("Aa").bits()[0], 0b1 << 27 | 0b1 << 1);
+    }
+
+    #[test]
+    fn test_find_items_fails() {
+        let why = find_items(Alphabet::standard(), "ab1", 7, 4)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(why, "line 7, column 6: unknown item '1'");
     }
 
     #[test]
     fn test_get_duplicate_item_priority() {
-        assert_eq!(get_duplicate_item_priority(&["abc", "ABc"]), 3);
-        assert_eq!(get_duplicate_item_priority(&["abC", "CBA"]), 29);
+        assert_eq!(
+            get_duplicate_item_priority(&[items("abc"), items("ABc")]),
+            3
+        );
+        assert_eq!(
+            get_duplicate_item_priority(&[items("abC"), items("CBA")]),
+            29
+        );
+    }
+
+    #[test]
+    fn test_priority_sum() -> Result<()> {
+        let example = "vJrwpWtwJgWrhcsFMMfFFhFp\n\
+                       jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\n\
+                       PmmdzqPrVvPwwTWBwg\n\
+                       wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\n\
+                       ttgJtRGJQctTZtZT\n\
+                       CrZsJsPPZsGzwwsLwLmpwMDw\n";
+        assert_eq!(
+            priority_sum(example, Mode::Compartments, Alphabet::standard())?,
+            157
+        );
+        assert_eq!(
+            priority_sum(example, Mode::Groups(3), Alphabet::standard())?,
+            70
+        );
+        assert_eq!(
+            priority_sum("abc\nAbC\n", Mode::Groups(2), Alphabet::standard())?,
+            2
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_priority_sum_fails() {
+        assert!(priority_sum("aba\n", Mode::Compartments, Alphabet::standard()).is_err());
+        assert!(priority_sum("ab\nbc\nbd\nxy\n", Mode::Groups(3), Alphabet::standard()).is_err());
+    }
+
+    #[test]
+    fn test_priority_sum_extended_alphabet() -> Result<()> {
+        let alphabet = Alphabet::from_table("a 1\nb 2\n0 100\n9 109\n- 200\n")?;
+        assert_eq!(
+            priority_sum("a9-9\nb0ba\n", Mode::Compartments, &alphabet)?,
+            109 + 2
+        );
+        let why = priority_sum("bb\nb0a?\n", Mode::Compartments, &alphabet)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(why, "line 2, column 4: unknown item '?'");
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_fromstr() -> Result<()> {
+        assert_eq!("compartments".parse::<Mode>()?, Mode::Compartments);
+        assert_eq!("groups".parse::<Mode>()?, Mode::Groups(3));
+        assert_eq!("groups:4".parse::<Mode>()?, Mode::Groups(4));
+        assert!("groups:0".parse::<Mode>().is_err());
+        assert!("halves".parse::<Mode>().is_err());
+        Ok(())
     }
 
     #[test]
     #[should_panic]
     fn test_get_duplicate_item_priority_panics_on_multiple_duplicates() {
-        get_duplicate_item_priority(&["abc", "dab"]);
+        get_duplicate_item_priority(&[items("abc"), items("dab")]);
     }
 }
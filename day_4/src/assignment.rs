@@ -0,0 +1,291 @@
+use anyhow::{bail, Error, Result};
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+/// An Assignment is an inclusive range of sections.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assignment {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Allen's interval relations, reading `a.relation(&b)` as "a <relation> b".
+///
+/// Assignments are compared as the sections they cover, so 1-2 meets 3-4 since nothing lies between them.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Relation {
+    Before,
+    Meets,
+    Overlaps,
+    Starts,
+    During,
+    Finishes,
+    Equals,
+    After,
+    MetBy,
+    OverlappedBy,
+    StartedBy,
+    Contains,
+    FinishedBy,
+}
+
+impl Relation {
+    /// Returns the relation seen from the other side, so that `b.relation(&a) == a.relation(&b).inverse()`.
+    // The puzzle only ever asks from one side, the inverses complete the algebra
+    #[allow(dead_code)]
+    pub fn inverse(self) -> Self {
+        match self {
+            Relation::Before => Relation::After,
+            Relation::Meets => Relation::MetBy,
+            Relation::Overlaps => Relation::OverlappedBy,
+            Relation::Starts => Relation::StartedBy,
+            Relation::During => Relation::Contains,
+            Relation::Finishes => Relation::FinishedBy,
+            Relation::Equals => Relation::Equals,
+            Relation::After => Relation::Before,
+            Relation::MetBy => Relation::Meets,
+            Relation::OverlappedBy => Relation::Overlaps,
+            Relation::StartedBy => Relation::Starts,
+            Relation::Contains => Relation::During,
+            Relation::FinishedBy => Relation::Finishes,
+        }
+    }
+}
+
+impl Assignment {
+    pub fn new(start: u32, end: u32) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns the bounds as a half-open range, wide enough that `end + 1` can't overflow.
+    fn bounds(&self) -> (u64, u64) {
+        (self.start.into(), u64::from(self.end) + 1)
+    }
+
+    pub fn relation(&self, other: &Self) -> Relation {
+        let (start, end) = self.bounds();
+        let (other_start, other_end) = other.bounds();
+
+        if end < other_start {
+            return Relation::Before;
+        } else if end == other_start {
+            return Relation::Meets;
+        } else if other_end < start {
+            return Relation::After;
+        } else if other_end == start {
+            return Relation::MetBy;
+        }
+        match (start.cmp(&other_start), end.cmp(&other_end)) {
+            (Ordering::Equal, Ordering::Equal) => Relation::Equals,
+            (Ordering::Equal, Ordering::Less) => Relation::Starts,
+            (Ordering::Equal, Ordering::Greater) => Relation::StartedBy,
+            (Ordering::Greater, Ordering::Equal) => Relation::Finishes,
+            (Ordering::Less, Ordering::Equal) => Relation::FinishedBy,
+            (Ordering::Greater, Ordering::Less) => Relation::During,
+            (Ordering::Less, Ordering::Greater) => Relation::Contains,
+            (Ordering::Less, Ordering::Less) => Relation::Overlaps,
+            (Ordering::Greater, Ordering::Greater) => Relation::OverlappedBy,
+        }
+    }
+
+    /// Returns true if every section of `other` is also part of this assignment.
+    pub fn contains(&self, other: &Self) -> bool {
+        matches!(
+            self.relation(other),
+            Relation::Equals | Relation::Contains | Relation::StartedBy | Relation::FinishedBy
+        )
+    }
+
+    /// Returns true if the assignments share at least one section.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !matches!(
+            self.relation(other),
+            Relation::Before | Relation::Meets | Relation::After | Relation::MetBy
+        )
+    }
+
+    /// Returns the number of sections.
+    pub fn len(&self) -> u64 {
+        let (start, end) = self.bounds();
+        end - start
+    }
+
+    /// Returns the sections found in both assignments, if any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        self.overlaps(other)
+            .then(|| Self::new(self.start.max(other.start), self.end.min(other.end)))
+    }
+
+    /// Returns the sections found in either assignment, unless they leave a gap between them.
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        match self.relation(other) {
+            Relation::Before | Relation::After => None,
+            _ => Some(Self::new(
+                self.start.min(other.start),
+                self.end.max(other.end),
+            )),
+        }
+    }
+
+    /// Returns the sections of this assignment that aren't in `other`, which may be split in two.
+    // Nothing is taken away from an assignment yet, but it rounds out the set operations
+    #[allow(dead_code)]
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if !self.overlaps(other) {
+            return vec![self.clone()];
+        }
+        let mut pieces = Vec::new();
+        if self.start < other.start {
+            pieces.push(Self::new(self.start, other.start - 1));
+        }
+        if self.end > other.end {
+            pieces.push(Self::new(other.end + 1, self.end));
+        }
+        pieces
+    }
+}
+
+impl FromStr for Assignment {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut result = Self { start: 0, end: 0 };
+
+        let inclusive_bounds: Vec<&str> = s.split("-").collect();
+        if inclusive_bounds.len() != 2 {
+            bail!("failed to parse assignment '{}'", s);
+        }
+
+        if let Ok(start) = inclusive_bounds[0].parse::<u32>() {
+            result.start = start;
+        } else {
+            bail!("failed to parse assignment '{}'", s);
+        }
+
+        if let Ok(end) = inclusive_bounds[1].parse::<u32>() {
+            result.end = end;
+        } else {
+            bail!("failed to parse assignment '{}'", s);
+        }
+
+        if result.start > result.end {
+            bail!("invalid assignment '{}'", s);
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_assignment_fromstr() -> Result<()> {
+        assert_eq!("1-2".parse::<Assignment>()?, assignment(1, 2));
+        assert_eq!("10-2000".parse::<Assignment>()?, assignment(10, 2000));
+        Ok(())
+    }
+
+    #[test]
+    fn test_assignment_fromstr_fails() {
+        assert!("a-b".parse::<Assignment>().is_err());
+        assert!("2-1".parse::<Assignment>().is_err());
+        assert!("2".parse::<Assignment>().is_err());
+        assert!("1-2-3".parse::<Assignment>().is_err());
+        assert!("".parse::<Assignment>().is_err());
+    }
+
+    #[test]
+    fn test_assignment_overlaps() {
+        assert!(assignment(1, 2).overlaps(&assignment(2, 3)));
+        assert!(assignment(1, 2).overlaps(&assignment(2, 3)));
+        assert!(assignment(1, 3).overlaps(&assignment(2, 3)));
+
+        assert!(assignment(2, 3).overlaps(&assignment(1, 2)));
+        assert!(assignment(2, 3).overlaps(&assignment(1, 2)));
+        assert!(assignment(2, 4).overlaps(&assignment(1, 2)));
+
+        assert!(assignment(1, 10).overlaps(&assignment(2, 2)));
+        assert!(assignment(2, 2).overlaps(&assignment(1, 10)));
+    }
+
+    #[test]
+    fn test_relation() {
+        let cases = [
+            ((1, 2), (4, 5), Relation::Before),
+            ((1, 2), (3, 5), Relation::Meets),
+            ((1, 3), (3, 5), Relation::Overlaps),
+            ((3, 4), (3, 5), Relation::Starts),
+            ((4, 4), (3, 5), Relation::During),
+            ((4, 5), (3, 5), Relation::Finishes),
+            ((3, 5), (3, 5), Relation::Equals),
+            ((7, 8), (3, 5), Relation::After),
+            ((6, 8), (3, 5), Relation::MetBy),
+            ((4, 8), (3, 5), Relation::OverlappedBy),
+            ((3, 8), (3, 5), Relation::StartedBy),
+            ((1, 8), (3, 5), Relation::Contains),
+            ((1, 5), (3, 5), Relation::FinishedBy),
+        ];
+        for ((a, b), (c, d), relation) in cases {
+            assert_eq!(assignment(a, b).relation(&assignment(c, d)), relation);
+        }
+        // Meeting at the very last section mustn't overflow
+        assert_eq!(
+            assignment(0, u32::MAX).relation(&assignment(u32::MAX, u32::MAX)),
+            Relation::FinishedBy
+        );
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = assignment(2, 8);
+        let b = assignment(5, 10);
+        assert_eq!(a.len(), 7);
+        assert_eq!(a.intersection(&b), Some(assignment(5, 8)));
+        assert_eq!(a.union(&b), Some(assignment(2, 10)));
+        assert_eq!(a.difference(&b), [assignment(2, 4)]);
+        assert_eq!(
+            a.difference(&assignment(4, 5)),
+            [assignment(2, 3), assignment(6, 8)]
+        );
+        assert_eq!(a.union(&assignment(9, 9)), Some(assignment(2, 9)));
+        assert_eq!(a.union(&assignment(10, 12)), None);
+        assert_eq!(a.intersection(&assignment(9, 9)), None);
+    }
+
+    fn assignment(start: u32, end: u32) -> Assignment {
+        Assignment { start, end }
+    }
+
+    /// Returns the sections of an assignment as bits, for brute force checks on small ranges.
+    fn sections(a: &Assignment) -> u64 {
+        (a.start..=a.end).fold(0, |acc, section| acc | 1 << section)
+    }
+
+    fn small_assignment() -> impl Strategy<Value = Assignment> {
+        (0u32..40, 0u32..20).prop_map(|(start, len)| assignment(start, start + len))
+    }
+
+    proptest! {
+        #[test]
+        fn prop_matches_brute_force(a in small_assignment(), b in small_assignment()) {
+            let (x, y) = (sections(&a), sections(&b));
+            prop_assert_eq!(a.contains(&b), x & y == y);
+            prop_assert_eq!(a.overlaps(&b), x & y != 0);
+            prop_assert_eq!(a.len(), u64::from(x.count_ones()));
+            prop_assert_eq!(a.intersection(&b).map_or(0, |i| sections(&i)), x & y);
+            prop_assert_eq!(
+                a.difference(&b).iter().fold(0, |acc, piece| acc | sections(piece)),
+                x & !y
+            );
+            // A union exists exactly when the combined sections have no gap
+            let union = x | y;
+            let contiguous = (union >> union.trailing_zeros()).count_ones()
+                == 64 - (union >> union.trailing_zeros()).leading_zeros();
+            prop_assert_eq!(a.union(&b).map(|u| sections(&u)), contiguous.then_some(union));
+            prop_assert_eq!(b.relation(&a), a.relation(&b).inverse());
+        }
+    }
+}
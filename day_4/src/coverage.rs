@@ -0,0 +1,138 @@
+use crate::assignment::Assignment;
+use std::fmt;
+
+/// How the sections of the whole camp are covered by its assignments.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Coverage {
+    /// Sections cleaned by at least one elf, merged into disjoint ranges.
+    pub covered: Vec<Assignment>,
+    /// Sections nobody cleans, between the first and last covered ones.
+    pub gaps: Vec<Assignment>,
+    /// The most elves assigned to a single section.
+    pub max_elves: usize,
+    /// Sections cleaned by `max_elves` elves.
+    pub hotspots: Vec<Assignment>,
+}
+
+impl Coverage {
+    /// Sweeps over the assignments' start and end points, keeping track of how many elves are in each section.
+    pub fn analyze(assignments: &[Assignment]) -> Self {
+        // An assignment adds an elf at its start and removes it after its end
+        let mut events: Vec<(u64, isize)> = assignments
+            .iter()
+            .flat_map(|a| [(u64::from(a.start), 1), (u64::from(a.end) + 1, -1)])
+            .collect();
+        events.sort_unstable();
+
+        // Runs of sections with the same number of elves, as (start, end, elves)
+        let mut runs: Vec<(u64, u64, usize)> = Vec::new();
+        let mut elves: isize = 0;
+        for (idx, (position, delta)) in events.iter().enumerate() {
+            elves += delta;
+            match events.get(idx + 1) {
+                Some((next, _)) if next > position => {
+                    runs.push((*position, next - 1, elves as usize));
+                }
+                _ => {}
+            }
+        }
+
+        let max_elves = runs.iter().map(|(_, _, elves)| *elves).max().unwrap_or(0);
+        let ranges = |keep: &dyn Fn(usize) -> bool| -> Vec<Assignment> {
+            let mut merged: Vec<Assignment> = Vec::new();
+            for (start, end, _) in runs.iter().filter(|(_, _, elves)| keep(*elves)) {
+                // Runs are sorted and sections fit in u32, as they come from assignments
+                let run = Assignment::new(*start as u32, *end as u32);
+                // Runs that touch join into one range
+                match merged.last_mut() {
+                    Some(last) => match last.union(&run) {
+                        Some(joined) => *last = joined,
+                        None => merged.push(run),
+                    },
+                    None => merged.push(run),
+                }
+            }
+            merged
+        };
+
+        Self {
+            covered: ranges(&|elves| elves > 0),
+            gaps: ranges(&|elves| elves == 0),
+            max_elves,
+            hotspots: if max_elves > 0 {
+                ranges(&|elves| elves == max_elves)
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    /// Returns the number of sections cleaned by at least one elf.
+    pub fn covered_sections(&self) -> u64 {
+        self.covered.iter().map(Assignment::len).sum()
+    }
+}
+
+/// Renders ranges compactly, e.g. `1-4,6,8-9`.
+pub struct Ranges<'a>(pub &'a [Assignment]);
+
+impl fmt::Display for Ranges<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "none");
+        }
+        for (idx, range) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ",")?;
+            }
+            if range.start == range.end {
+                write!(f, "{}", range.start)?;
+            } else {
+                write!(f, "{}-{}", range.start, range.end)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ranges;
+
+    #[test]
+    fn test_analyze() {
+        let coverage =
+            Coverage::analyze(&ranges("2-4,6-8,2-3,4-5,5-7,7-9,2-8,3-7,6-6,4-6,2-6,4-8"));
+        assert_eq!(coverage.covered, ranges("2-9"));
+        assert_eq!(coverage.gaps, []);
+        assert_eq!(coverage.covered_sections(), 8);
+        assert_eq!(coverage.max_elves, 8);
+        assert_eq!(coverage.hotspots, ranges("6-6"));
+    }
+
+    #[test]
+    fn test_analyze_gaps() {
+        let coverage = Coverage::analyze(&ranges("10-12,1-2,3-4,20-20,11-11"));
+        assert_eq!(coverage.covered, ranges("1-4,10-12,20-20"));
+        assert_eq!(coverage.gaps, ranges("5-9,13-19"));
+        assert_eq!(coverage.max_elves, 2);
+        assert_eq!(coverage.hotspots, ranges("11-11"));
+        assert_eq!(Ranges(&coverage.covered).to_string(), "1-4,10-12,20");
+        assert_eq!(Ranges(&coverage.gaps).to_string(), "5-9,13-19");
+    }
+
+    #[test]
+    fn test_analyze_empty() {
+        let coverage = Coverage::analyze(&[]);
+        assert_eq!(coverage.max_elves, 0);
+        assert!(coverage.covered.is_empty() && coverage.hotspots.is_empty());
+        assert_eq!(Ranges(&coverage.gaps).to_string(), "none");
+    }
+
+    #[test]
+    fn test_analyze_last_section() {
+        let coverage = Coverage::analyze(&[Assignment::new(u32::MAX - 1, u32::MAX)]);
+        assert_eq!(coverage.covered, [Assignment::new(u32::MAX - 1, u32::MAX)]);
+    }
+}
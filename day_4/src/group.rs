@@ -0,0 +1,93 @@
+use crate::assignment::Assignment;
+use anyhow::{bail, Result};
+
+/// Parses a crew's comma-separated assignments, e.g. `2-4,6-8,3-5`.
+pub fn parse_group(s: &str) -> Result<Vec<Assignment>> {
+    if s.trim().is_empty() {
+        bail!("empty line");
+    }
+    s.split(',')
+        .enumerate()
+        .map(|(idx, each)| {
+            if each.is_empty() {
+                bail!("empty range {}", idx + 1);
+            }
+            each.parse::<Assignment>()
+        })
+        .collect()
+}
+
+/// Iterates over every pair of elves of a group, each pair once.
+pub fn pairs(group: &[Assignment]) -> impl Iterator<Item = (&Assignment, &Assignment)> {
+    group
+        .iter()
+        .enumerate()
+        .flat_map(move |(idx, left)| group[idx + 1..].iter().map(move |right| (left, right)))
+}
+
+/// Returns true if an elf's assignment is fully contained by another's.
+pub fn has_containment(group: &[Assignment]) -> bool {
+    pairs(group).any(|(left, right)| left.contains(right) || right.contains(left))
+}
+
+/// Returns true if any two elves share a section.
+pub fn has_overlap(group: &[Assignment]) -> bool {
+    pairs(group).any(|(left, right)| left.overlaps(right))
+}
+
+/// Returns the sections every elf of the group cleans, if there are any.
+pub fn common_sections(group: &[Assignment]) -> Option<Assignment> {
+    let (first, rest) = group.split_first()?;
+    rest.iter()
+        .try_fold(first.clone(), |acc, each| acc.intersection(each))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_group() -> Result<()> {
+        assert_eq!(parse_group("2-4")?.len(), 1);
+        assert_eq!(
+            parse_group("2-4,6-8,3-5")?,
+            [
+                Assignment::new(2, 4),
+                Assignment::new(6, 8),
+                Assignment::new(3, 5)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_group_fails() {
+        assert_eq!(parse_group("  ").unwrap_err().to_string(), "empty line");
+        assert_eq!(
+            parse_group("2-4,,3-5").unwrap_err().to_string(),
+            "empty range 2"
+        );
+        assert!(parse_group("2-4,6").is_err());
+        assert!(parse_group("2-4,6-8-9").is_err());
+    }
+
+    #[test]
+    fn test_group_counts() -> Result<()> {
+        let trio = parse_group("2-4,6-8,3-8")?;
+        assert_eq!(pairs(&trio).count(), 3);
+        assert!(has_containment(&trio));
+        assert!(has_overlap(&trio));
+        assert_eq!(common_sections(&trio), None);
+
+        let quartet = parse_group("2-6,4-8,5-5,1-9")?;
+        assert_eq!(common_sections(&quartet), Some(Assignment::new(5, 5)));
+
+        let apart = parse_group("1-2,4-5,7-8")?;
+        assert!(!has_containment(&apart) && !has_overlap(&apart));
+
+        let single = parse_group("1-2")?;
+        assert!(!has_containment(&single) && !has_overlap(&single));
+        assert_eq!(common_sections(&single), Some(Assignment::new(1, 2)));
+        Ok(())
+    }
+}
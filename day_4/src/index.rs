@@ -0,0 +1,136 @@
+use crate::assignment::Assignment;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Finds the assignments overlapping a section or range, across the whole camp.
+///
+/// Assignments are sorted by start and read as an implicit balanced tree, where the middle of every slice is a node
+/// and each node remembers the furthest end within its subtree. Queries skip subtrees that end too early or start
+/// too late, so they take O(log n + k) for k results.
+pub struct AssignmentIndex {
+    /// Assignments with the id they were added with, sorted by start.
+    entries: Vec<(Assignment, usize)>,
+    /// The furthest end of the subtree rooted at each entry.
+    max_end: Vec<u32>,
+}
+
+impl AssignmentIndex {
+    /// Indexes assignments, identifying each by its position in the slice.
+    pub fn new(assignments: &[Assignment]) -> Self {
+        let mut entries: Vec<(Assignment, usize)> = assignments.iter().cloned().zip(0..).collect();
+        entries.sort_by_key(|(a, id)| (a.start, a.end, *id));
+        let mut index = Self {
+            max_end: vec![0; entries.len()],
+            entries,
+        };
+        index.build(0, index.entries.len());
+        index
+    }
+
+    fn build(&mut self, lo: usize, hi: usize) -> Option<u32> {
+        if lo >= hi {
+            return None;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let max_end = [self.build(lo, mid), self.build(mid + 1, hi)]
+            .into_iter()
+            .flatten()
+            .fold(self.entries[mid].0.end, u32::max);
+        self.max_end[mid] = max_end;
+        Some(max_end)
+    }
+
+    /// Returns the ids of the assignments that clean `section`, in order of their start.
+    pub fn stab(&self, section: u32) -> Vec<usize> {
+        self.overlapping(&Assignment::new(section, section))
+    }
+
+    /// Returns the ids of the assignments sharing a section with `range`, in order of their start.
+    pub fn overlapping(&self, range: &Assignment) -> Vec<usize> {
+        let mut ids = Vec::new();
+        self.visit(0, self.entries.len(), range, &mut ids);
+        ids
+    }
+
+    fn visit(&self, lo: usize, hi: usize, range: &Assignment, ids: &mut Vec<usize>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        if self.max_end[mid] < range.start {
+            return;
+        }
+        self.visit(lo, mid, range, ids);
+        let (assignment, id) = &self.entries[mid];
+        // Everything from here on starts too late
+        if assignment.start > range.end {
+            return;
+        }
+        if assignment.overlaps(range) {
+            ids.push(*id);
+        }
+        self.visit(mid + 1, hi, range, ids);
+    }
+
+    /// Counts the pairs of indexed assignments that share at least one section, in O(n log n).
+    pub fn count_overlapping_pairs(&self) -> usize {
+        // Sweep by start, keeping the ends of the assignments still open
+        let mut open: BinaryHeap<Reverse<u32>> = BinaryHeap::new();
+        let mut pairs = 0;
+        for (assignment, _) in &self.entries {
+            while open
+                .peek()
+                .is_some_and(|Reverse(end)| *end < assignment.start)
+            {
+                open.pop();
+            }
+            pairs += open.len();
+            open.push(Reverse(assignment.end));
+        }
+        pairs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{ranges, scattered};
+
+    #[test]
+    fn test_queries() {
+        let assignments = ranges("2-4,6-8,2-3,4-5,5-7,7-9,2-8,3-7");
+        let index = AssignmentIndex::new(&assignments);
+        assert_eq!(index.stab(1), []);
+        assert_eq!(index.stab(4), [0, 6, 7, 3]);
+        assert_eq!(index.stab(9), [5]);
+        assert_eq!(index.overlapping(&Assignment::new(8, 20)), [6, 1, 5]);
+        assert_eq!(index.overlapping(&Assignment::new(10, 20)), []);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let assignments = scattered(300, 101, 17);
+        let index = AssignmentIndex::new(&assignments);
+
+        for start in 0..120 {
+            let range = Assignment::new(start, start + start % 5);
+            let mut expected: Vec<usize> = (0..assignments.len())
+                .filter(|id| assignments[*id].overlaps(&range))
+                .collect();
+            let mut found = index.overlapping(&range);
+            expected.sort();
+            found.sort();
+            assert_eq!(found, expected);
+        }
+
+        let mut pairs = 0;
+        for a in 0..assignments.len() {
+            for b in (a + 1)..assignments.len() {
+                if assignments[a].overlaps(&assignments[b]) {
+                    pairs += 1;
+                }
+            }
+        }
+        assert_eq!(index.count_overlapping_pairs(), pairs);
+    }
+}
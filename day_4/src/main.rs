@@ -1,9 +1,48 @@
-use anyhow::{anyhow, bail, Error, Result};
+use anyhow::{anyhow, Result};
+use std::env;
 use std::fs;
 use std::path::Path;
-use std::str::FromStr;
 
-fn main() {
+mod assignment;
+mod coverage;
+mod group;
+mod index;
+mod plan;
+mod report;
+#[cfg(test)]
+mod testing;
+use assignment::Assignment;
+use coverage::{Coverage, Ranges};
+use group::{common_sections, has_containment, has_overlap, pairs, parse_group};
+use index::AssignmentIndex;
+use plan::{freeable, minimum_cover, saved_sections};
+use report::Report;
+
+fn main() -> Result<()> {
+    let mut report = false;
+    let mut coverage = false;
+    let mut sections: Vec<u32> = Vec::new();
+    let mut ranges: Vec<Assignment> = Vec::new();
+    let mut cross_pairs = false;
+    let mut minimize = false;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or(anyhow!("missing value for argument '{}'", arg))
+        };
+        match arg.as_str() {
+            "--report" => report = true,
+            "--coverage" => coverage = true,
+            "--stab" => sections.push(value()?.parse()?),
+            "--range" => ranges.push(value()?.parse()?),
+            "--cross-pairs" => cross_pairs = true,
+            "--minimize" => minimize = true,
+            _ => return Err(anyhow!("unknown argument '{}'", arg)),
+        }
+    }
+
     let input_path = Path::new("input.txt");
 
     let content = match fs::read_to_string(input_path) {
@@ -11,117 +50,137 @@ fn main() {
         Ok(content) => content,
     };
 
-    let num_overlaps: Result<u32> = content
-        .split("\n")
-        .filter(|line| line.len() > 0)
-        .map(|line| parse_assignments(line))
-        .map(|result: Result<Vec<Assignment>>| {
-            result.map(|assignments| {
-                let overlaps = assignments[0].overlaps(&assignments[1]);
-                if !overlaps {
-                    println!("{:?} {:?}", assignments[0], assignments[1]);
-                }
-                overlaps
-            })
+    // Keep the line numbers to tell elves apart and report errors
+    let lines: Vec<(usize, &str)> = content
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line))
+        .collect();
+    let groups: Vec<Vec<Assignment>> = lines
+        .iter()
+        .map(|(line_number, line)| {
+            parse_group(line).map_err(|why| anyhow!("line {}: {}", line_number, why))
         })
-        .try_fold(0, |acc, result| {
-            result.map(|overlaps| if overlaps { acc + 1 } else { acc })
-        });
-
-    match num_overlaps {
-        Ok(num_overlaps) => println!("Total: {}", num_overlaps),
-        Err(why) => panic!("{}", why),
-    }
-}
-
-fn parse_assignments(s: &str) -> Result<Vec<Assignment>> {
-    s.split(",")
-        .map(|each| each.parse::<Assignment>())
-        .collect()
-}
-
-/// An Assignment is an inclusive range of sections.
-#[derive(Clone, Debug, PartialEq)]
-struct Assignment {
-    start: u32,
-    end: u32,
-}
-
-impl Assignment {
-    fn contains(&self, other: &Self) -> bool {
-        self.start <= other.start && self.end >= other.end
-    }
-
-    fn overlaps(&self, other: &Self) -> bool {
-        self.start <= other.end && self.start >= other.start
-            || self.end <= other.end && self.end >= other.start
-            || self.contains(other)
+        .collect::<Result<_>>()?;
+    // Every elf of the camp as (line number, position on the line, assignment)
+    let elves: Vec<(usize, usize, Assignment)> = lines
+        .iter()
+        .zip(&groups)
+        .flat_map(|((line_number, _), group)| {
+            group
+                .iter()
+                .enumerate()
+                .map(|(idx, assignment)| (*line_number, idx + 1, assignment.clone()))
+        })
+        .collect();
+    // Pairs of elves working on the same line
+    let pairs: Vec<(Assignment, Assignment)> = groups
+        .iter()
+        .flat_map(|group| pairs(group).map(|(left, right)| (left.clone(), right.clone())))
+        .collect();
+
+    if report {
+        print!("{}", Report::new(&pairs));
+        println!();
     }
-}
 
-impl FromStr for Assignment {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self> {
-        let mut result = Self { start: 0, end: 0 };
-
-        let inclusive_bounds: Vec<&str> = s.split("-").collect();
-
-        if let Ok(start) = inclusive_bounds[0].parse::<u32>() {
-            result.start = start;
-        } else {
-            bail!("failed to parse assignment '{}'", s);
+    let assignments: Vec<Assignment> = elves.iter().map(|(_, _, a)| a.clone()).collect();
+
+    if !sections.is_empty() || !ranges.is_empty() || cross_pairs {
+        let index = AssignmentIndex::new(&assignments);
+        let print_elves = |ids: Vec<usize>| {
+            for id in ids {
+                let (line_number, position, assignment) = &elves[id];
+                println!(
+                    "  line {}, elf {}: {}",
+                    line_number,
+                    position,
+                    Ranges(std::slice::from_ref(assignment))
+                );
+            }
+        };
+        for section in &sections {
+            println!("Elves on {}:", section);
+            print_elves(index.stab(*section));
         }
-
-        if let Ok(end) = inclusive_bounds[1].parse::<u32>() {
-            result.end = end;
-        } else {
-            bail!("failed to parse assignment '{}'", s);
+        for range in &ranges {
+            println!("Elves on {}:", Ranges(std::slice::from_ref(range)));
+            print_elves(index.overlapping(range));
         }
-
-        if result.start > result.end {
-            bail!("invalid assignment '{}'", s);
+        if cross_pairs {
+            let same_line = pairs
+                .iter()
+                .filter(|(left, right)| left.overlaps(right))
+                .count();
+            println!(
+                "Overlapping pairs across lines: {}",
+                index.count_overlapping_pairs() - same_line
+            );
         }
-
-        return Ok(result);
+        println!();
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_assignment_fromstr() -> Result<()> {
-        assert_eq!("1-2".parse::<Assignment>()?, assignment(1, 2));
-        assert_eq!("10-2000".parse::<Assignment>()?, assignment(10, 2000));
-        Ok(())
+    if coverage {
+        let coverage = Coverage::analyze(&assignments);
+        println!(
+            "Covered: {} ({} sections)",
+            Ranges(&coverage.covered),
+            coverage.covered_sections()
+        );
+        println!("Gaps: {}", Ranges(&coverage.gaps));
+        println!("Most elves on a section: {}", coverage.max_elves);
+        println!("Hotspots: {}", Ranges(&coverage.hotspots));
+        println!();
     }
 
-    #[test]
-    fn test_assignment_fromstr_fails() {
-        assert!("a-b".parse::<Assignment>().is_err());
-        assert!("2-1".parse::<Assignment>().is_err());
+    if minimize {
+        let describe = |id: usize| {
+            let (line_number, position, assignment) = &elves[id];
+            format!(
+                "line {}, elf {}: {}",
+                line_number,
+                position,
+                Ranges(std::slice::from_ref(assignment))
+            )
+        };
+
+        let freed = freeable(&assignments);
+        println!("Elves that could be freed:");
+        for (id, by) in &freed {
+            println!("  {} (inside {})", describe(*id), describe(*by));
+        }
+        let mut is_freed = vec![false; assignments.len()];
+        for (id, _) in &freed {
+            is_freed[*id] = true;
+        }
+        let kept: Vec<usize> = (0..assignments.len()).filter(|id| !is_freed[*id]).collect();
+        println!("Sections saved: {}", saved_sections(&assignments, &kept));
+
+        let cover = minimum_cover(&assignments);
+        println!(
+            "Smallest crew covering every section ({} of {} elves):",
+            cover.len(),
+            assignments.len()
+        );
+        for id in &cover {
+            println!("  {}", describe(*id));
+        }
+        println!("Sections saved: {}", saved_sections(&assignments, &cover));
+        println!();
     }
 
-    #[test]
-    fn test_assignment_overlaps() {
-        assert!(assignment(1, 2).overlaps(&assignment(2, 3)));
-        assert!(assignment(1, 2).overlaps(&assignment(2, 3)));
-        assert!(assignment(1, 3).overlaps(&assignment(2, 3)));
-
-        assert!(assignment(2, 3).overlaps(&assignment(1, 2)));
-        assert!(assignment(2, 3).overlaps(&assignment(1, 2)));
-        assert!(assignment(2, 4).overlaps(&assignment(1, 2)));
-
-        assert!(assignment(1, 10).overlaps(&assignment(2, 2)));
-        assert!(assignment(2, 2).overlaps(&assignment(1, 10)));
+    let num_contained = groups.iter().filter(|group| has_containment(group)).count();
+    let num_overlaps = groups.iter().filter(|group| has_overlap(group)).count();
+    println!("Contained: {}", num_contained);
+    println!("Overlapping: {}", num_overlaps);
+    // With pairs this is the same as overlapping, larger crews may overlap without a section in common
+    if groups.iter().any(|group| group.len() > 2) {
+        let num_common = groups
+            .iter()
+            .filter(|group| group.len() > 1 && common_sections(group).is_some())
+            .count();
+        println!("Overlapping all: {}", num_common);
     }
 
-    fn assignment(start: u32, end: u32) -> Assignment {
-        Assignment {
-            start: start,
-            end: end,
-        }
-    }
+    Ok(())
 }
@@ -0,0 +1,113 @@
+use crate::assignment::Assignment;
+
+/// Returns the elves that could be freed because another elf's assignment contains theirs, as `(freed, by)` ids.
+///
+/// Of several equal assignments the first one is kept. Ids are positions in `assignments`.
+pub fn freeable(assignments: &[Assignment]) -> Vec<(usize, usize)> {
+    // Sorted by start and then widest first, anything containing an assignment comes before it
+    let mut order: Vec<usize> = (0..assignments.len()).collect();
+    order.sort_by_key(|id| {
+        let a = &assignments[*id];
+        (a.start, std::cmp::Reverse(a.end), *id)
+    });
+
+    let mut freed = Vec::new();
+    // The assignment reaching furthest so far is the only one that may contain the next, and since the next starts no
+    // earlier, anything it doesn't contain reaches further still
+    let mut widest: Option<usize> = None;
+    for id in order {
+        match widest {
+            Some(by) if assignments[by].contains(&assignments[id]) => freed.push((id, by)),
+            _ => widest = Some(id),
+        }
+    }
+    freed.sort();
+    freed
+}
+
+/// Returns the fewest elves that still cover every section covered today, in order of their start.
+///
+/// Greedy: from the first section not yet covered, keep whichever assignment starting there or earlier reaches
+/// furthest.
+pub fn minimum_cover(assignments: &[Assignment]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..assignments.len()).collect();
+    order.sort_by_key(|id| (assignments[*id].start, *id));
+
+    let mut kept = Vec::new();
+    // The first section not covered yet, wide enough to go past the last possible one
+    let mut next: u64 = 0;
+    let mut idx = 0;
+    while idx < order.len() {
+        // Skip the gap up to the next assignment, if there is one
+        next = next.max(assignments[order[idx]].start.into());
+
+        let mut best: Option<usize> = None;
+        while idx < order.len() && u64::from(assignments[order[idx]].start) <= next {
+            let id = order[idx];
+            if best.is_none_or(|best| assignments[id].end > assignments[best].end) {
+                best = Some(id);
+            }
+            idx += 1;
+        }
+        if let Some(best) = best {
+            if u64::from(assignments[best].end) >= next {
+                kept.push(best);
+                next = u64::from(assignments[best].end) + 1;
+            }
+        }
+    }
+    kept
+}
+
+/// Returns how many section cleanings are saved by only keeping the elves in `kept`.
+pub fn saved_sections(assignments: &[Assignment], kept: &[usize]) -> u64 {
+    let total: u64 = assignments.iter().map(Assignment::len).sum();
+    total - kept.iter().map(|id| assignments[*id].len()).sum::<u64>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coverage::Coverage;
+    use crate::testing::{ranges, scattered};
+
+    #[test]
+    fn test_freeable() {
+        let assignments = ranges("2-8,3-7,6-6,4-6,2-8,9-9,1-3");
+        assert_eq!(freeable(&assignments), [(1, 0), (2, 0), (3, 0), (4, 0)]);
+        assert_eq!(freeable(&ranges("1-3,2-4,3-5")), []);
+    }
+
+    #[test]
+    fn test_minimum_cover() {
+        let assignments = ranges("2-4,6-8,2-3,4-5,5-7,7-9,2-8,3-7,12-13,13-15");
+        let kept = minimum_cover(&assignments);
+        assert_eq!(kept, [6, 5, 8, 9]);
+        assert_eq!(saved_sections(&assignments, &kept), 33 - 15);
+    }
+
+    #[test]
+    fn test_minimum_cover_keeps_coverage() {
+        let assignments = scattered(200, 301, 11);
+        let kept = minimum_cover(&assignments);
+        let kept_assignments: Vec<Assignment> =
+            kept.iter().map(|id| assignments[*id].clone()).collect();
+        assert_eq!(
+            Coverage::analyze(&kept_assignments).covered,
+            Coverage::analyze(&assignments).covered
+        );
+        // Dropping any kept elf must leave a hole
+        for skip in 0..kept_assignments.len() {
+            let mut fewer = kept_assignments.clone();
+            fewer.remove(skip);
+            assert_ne!(
+                Coverage::analyze(&fewer).covered,
+                Coverage::analyze(&assignments).covered
+            );
+        }
+        // Nobody freed by containment is needed for the cover
+        for (freed, _) in freeable(&assignments) {
+            assert!(!kept.contains(&freed));
+        }
+    }
+}
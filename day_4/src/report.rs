@@ -0,0 +1,152 @@
+use crate::assignment::{Assignment, Relation};
+use std::fmt;
+
+/// How the two assignments of a pair relate to each other.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PairRelation {
+    /// Separated by at least one section nobody in the pair cleans.
+    Disjoint,
+    /// Next to each other without sharing a section.
+    Touching,
+    /// Sharing some sections, but neither contains the other.
+    Overlapping,
+    LeftContainsRight,
+    RightContainsLeft,
+    Equal,
+}
+
+impl PairRelation {
+    pub const ALL: [PairRelation; 6] = [
+        PairRelation::Disjoint,
+        PairRelation::Touching,
+        PairRelation::Overlapping,
+        PairRelation::LeftContainsRight,
+        PairRelation::RightContainsLeft,
+        PairRelation::Equal,
+    ];
+
+    pub fn classify(left: &Assignment, right: &Assignment) -> Self {
+        match left.relation(right) {
+            Relation::Before | Relation::After => PairRelation::Disjoint,
+            Relation::Meets | Relation::MetBy => PairRelation::Touching,
+            Relation::Overlaps | Relation::OverlappedBy => PairRelation::Overlapping,
+            Relation::Contains | Relation::StartedBy | Relation::FinishedBy => {
+                PairRelation::LeftContainsRight
+            }
+            Relation::During | Relation::Starts | Relation::Finishes => {
+                PairRelation::RightContainsLeft
+            }
+            Relation::Equals => PairRelation::Equal,
+        }
+    }
+}
+
+impl fmt::Display for PairRelation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PairRelation::Disjoint => "disjoint",
+            PairRelation::Touching => "touching",
+            PairRelation::Overlapping => "overlapping",
+            PairRelation::LeftContainsRight => "left contains right",
+            PairRelation::RightContainsLeft => "right contains left",
+            PairRelation::Equal => "equal",
+        };
+        f.pad(name)
+    }
+}
+
+/// Classifies every pair of assignments and renders them with per-relation totals.
+pub struct Report<'a> {
+    pairs: &'a [(Assignment, Assignment)],
+}
+
+impl<'a> Report<'a> {
+    pub fn new(pairs: &'a [(Assignment, Assignment)]) -> Self {
+        Self { pairs }
+    }
+
+    /// Returns how many pairs fall in each relation, in the order of PairRelation::ALL.
+    pub fn totals(&self) -> Vec<(PairRelation, usize)> {
+        PairRelation::ALL
+            .into_iter()
+            .map(|relation| {
+                let count = self
+                    .pairs
+                    .iter()
+                    .filter(|(left, right)| PairRelation::classify(left, right) == relation)
+                    .count();
+                (relation, count)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (left, right) in self.pairs {
+            writeln!(
+                f,
+                "{}-{},{}-{}: {}",
+                left.start,
+                left.end,
+                right.start,
+                right.end,
+                PairRelation::classify(left, right)
+            )?;
+        }
+        writeln!(f)?;
+        for (relation, count) in self.totals() {
+            writeln!(f, "{:<20} {:>6}", relation, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(s: &str) -> (Assignment, Assignment) {
+        let (left, right) = s.split_once(',').unwrap();
+        (left.parse().unwrap(), right.parse().unwrap())
+    }
+
+    #[test]
+    fn test_classify() {
+        let cases = [
+            ("2-4,6-8", PairRelation::Disjoint),
+            ("2-3,4-5", PairRelation::Touching),
+            ("6-8,2-5", PairRelation::Touching),
+            ("5-7,7-9", PairRelation::Overlapping),
+            ("2-8,3-7", PairRelation::LeftContainsRight),
+            ("6-6,4-6", PairRelation::RightContainsLeft),
+            ("3-5,3-5", PairRelation::Equal),
+        ];
+        for (s, relation) in cases {
+            let (left, right) = pair(s);
+            assert_eq!(PairRelation::classify(&left, &right), relation, "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_totals() {
+        let pairs: Vec<_> = [
+            "2-4,6-8", "2-3,4-5", "5-7,7-9", "2-8,3-7", "6-6,4-6", "2-6,4-8",
+        ]
+        .into_iter()
+        .map(pair)
+        .collect();
+        let totals = Report::new(&pairs).totals();
+        assert_eq!(
+            totals,
+            [
+                (PairRelation::Disjoint, 1),
+                (PairRelation::Touching, 1),
+                (PairRelation::Overlapping, 2),
+                (PairRelation::LeftContainsRight, 1),
+                (PairRelation::RightContainsLeft, 1),
+                (PairRelation::Equal, 0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,16 @@
+use crate::assignment::Assignment;
+
+/// Parses comma separated assignments, e.g. `2-4,6-8`.
+pub fn ranges(s: &str) -> Vec<Assignment> {
+    s.split(',').map(|each| each.parse().unwrap()).collect()
+}
+
+/// A fixed but scattered camp of `count` assignments, starting below `spread` and up to `max_len` sections long.
+pub fn scattered(count: u32, spread: u32, max_len: u32) -> Vec<Assignment> {
+    (0..count)
+        .map(|i| {
+            let start = i * 37 % spread;
+            Assignment::new(start, start + i * 13 % max_len)
+        })
+        .collect()
+}
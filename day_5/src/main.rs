@@ -1,8 +1,35 @@
 use anyhow::{anyhow, bail, Error, Result};
+use std::env;
+use std::fmt;
 use std::fs;
+use std::io::{self, BufRead, Write};
 use std::path::Path;
+use std::str::FromStr;
+
+mod replay;
+mod validate;
+use replay::Replay;
+use validate::validate;
 
 fn main() -> Result<(), Error> {
+    let mut replay_model: Option<CraneModel> = None;
+    let mut check = false;
+    let mut label_width = 1;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or(anyhow!("missing value for argument '{}'", arg))
+        };
+        match arg.as_str() {
+            "--replay" => replay_model = Some(value()?.parse()?),
+            "--check" => check = true,
+            "--label-width" => label_width = value()?.parse()?,
+            _ => return Err(anyhow!("unknown argument '{}'", arg)),
+        }
+    }
+
     let input_path = Path::new("input.txt");
 
     let content = match fs::read_to_string(input_path) {
@@ -10,23 +37,158 @@ fn main() -> Result<(), Error> {
         Ok(content) => content,
     };
 
-    let mut yard = parse_yard(&content)?;
+    let yard = parse_yard(&content, label_width)?;
+    // The procedure follows the drawing, keep the line numbers to report invalid moves
+    let procedure: Vec<(usize, &str)> = content
+        .split('\n')
+        .enumerate()
+        .skip_while(|(_, line)| !line.is_empty())
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(idx, line)| (idx + 1, line))
+        .collect();
+
+    // Check the whole procedure up front rather than stopping at the first impossible move
+    let numbered_actions = match validate(&yard, &procedure) {
+        Ok(actions) => actions,
+        Err(invalid) => {
+            for invalid_move in &invalid {
+                eprintln!("{}", invalid_move);
+            }
+            bail!("{} invalid moves", invalid.len());
+        }
+    };
+    if check {
+        println!("All {} moves are valid", numbered_actions.len());
+        return Ok(());
+    }
+
+    let actions: Vec<Action> = numbered_actions
+        .into_iter()
+        .map(|(_, action)| action)
+        .collect();
 
-    for line in content.split("\n").filter(|line| line.starts_with("move")) {
-        yard.crane_lift(parse_action(line)?)?;
+    if let Some(model) = replay_model {
+        return run_replay(Replay::new(yard, &actions, model)?);
     }
 
-    let tops: String = yard
-        .stacks
-        .iter()
-        .map(|stack| stack.last().ok_or(anyhow!("empty stack")))
-        .collect::<Result<_>>()?;
-    println!("{}", tops);
+    for model in [CraneModel::CrateMover9000, CraneModel::CrateMover9001] {
+        let mut yard = yard.clone();
+        for action in &actions {
+            yard.crane_lift(action, model)?;
+        }
+
+        let tops: String = yard
+            .stacks
+            .iter()
+            .map(|stack| {
+                stack
+                    .last()
+                    .map(String::as_str)
+                    .ok_or(anyhow!("empty stack"))
+            })
+            .collect::<Result<_>>()?;
+        println!("{}: {}", model, tops);
+    }
 
     Ok(())
 }
 
-type Stack = Vec<char>;
+/// Steps through a replay with commands read from stdin.
+fn run_replay(mut replay: Replay) -> Result<()> {
+    println!("Commands: [n]ext (or enter), [b]ack, [g]oto <step>, [d]iff <step> <step>, [q]uit");
+    println!("{}", replay);
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("step {}/{}> ", replay.step(), replay.len());
+        io::stdout().flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            return Ok(());
+        };
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let moved = match words.as_slice() {
+            [] | ["n"] => replay.forward(),
+            ["b"] => replay.back(),
+            ["g", step] => match step.parse() {
+                Ok(step) => replay.seek(step).map(|_| true).unwrap_or_else(|why| {
+                    println!("{}", why);
+                    false
+                }),
+                Err(why) => {
+                    println!("invalid step '{}': {}", step, why);
+                    false
+                }
+            },
+            ["d", a, b] => {
+                match (a.parse(), b.parse()) {
+                    (Ok(a), Ok(b)) => match replay.diff(a, b) {
+                        Ok(diffs) if diffs.is_empty() => println!("no differences"),
+                        Ok(diffs) => diffs.iter().for_each(|diff| println!("{}", diff)),
+                        Err(why) => println!("{}", why),
+                    },
+                    _ => println!("invalid steps '{}' '{}'", a, b),
+                }
+                false
+            }
+            ["q"] => return Ok(()),
+            _ => {
+                println!("unknown command '{}'", line);
+                false
+            }
+        };
+
+        if moved {
+            if let Some(effect) = replay.last_effect() {
+                println!("after {}", effect.action);
+            }
+            println!("{}", replay);
+            let tops: String = replay
+                .yard()
+                .stacks
+                .iter()
+                .map(|stack| stack.last().map_or(" ", String::as_str))
+                .collect();
+            println!("tops: {}", tops);
+        }
+    }
+}
+
+/// Which crane moves the crates, they differ in how many crates they can lift at once.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CraneModel {
+    /// Moves crates one at a time, so a move reverses their order.
+    CrateMover9000,
+    /// Moves crates all at once, keeping their order.
+    CrateMover9001,
+}
+
+impl fmt::Display for CraneModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CraneModel::CrateMover9000 => write!(f, "CrateMover 9000"),
+            CraneModel::CrateMover9001 => write!(f, "CrateMover 9001"),
+        }
+    }
+}
+
+/// Parses a model number, `9000` or `9001`.
+impl FromStr for CraneModel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "9000" => Ok(CraneModel::CrateMover9000),
+            "9001" => Ok(CraneModel::CrateMover9001),
+            _ => Err(anyhow!("unknown crane model '{}'", s)),
+        }
+    }
+}
+
+/// A crate's label, all crates of a yard's drawing have labels of the same width.
+type Crate = String;
+type Stack = Vec<Crate>;
 
 #[derive(Clone, Debug)]
 struct Yard {
@@ -34,35 +196,98 @@ struct Yard {
 }
 
 impl Yard {
-    fn new() -> Self {
-        Self { stacks: Vec::new() }
-    }
-
-    fn crane_lift(&mut self, action: Action) -> Result<()> {
-        // Our internal stacks are zero indexed, adjust the index
-        let from_stack = self
-            .stacks
-            .get_mut((action.from - 1) as usize)
-            .ok_or(anyhow!("no such stack"))?;
+    fn crane_lift(&mut self, action: &Action, model: CraneModel) -> Result<()> {
+        // Our internal stacks are zero indexed, adjust the indices
+        let stack_index = |stack: u32| {
+            stack
+                .checked_sub(1)
+                .map(|idx| idx as usize)
+                .filter(|idx| *idx < self.stacks.len())
+                .ok_or(anyhow!("no such stack {}", stack))
+        };
+        let from = stack_index(action.from)?;
+        let to = stack_index(action.to)?;
+        // Taking crates off a stack and putting them back must not reorder them
+        if from == to {
+            return Err(anyhow!(
+                "cannot move crates onto the same stack {}",
+                action.from
+            ));
+        }
 
+        let from_stack = &mut self.stacks[from];
         if action.count as usize > from_stack.len() {
             return Err(anyhow!("not enough crates remaining"));
         }
 
         let at = from_stack.len() - action.count as usize;
         let mut crates = from_stack.split_off(at);
-
-        // Our internal stacks are zero indexed, adjust the index
-        let to_stack = self
-            .stacks
-            .get_mut((action.to - 1) as usize)
-            .ok_or(anyhow!("no such stack"))?;
-        to_stack.append(&mut crates);
+        // Lifting the crates one by one is the same as moving them all at once in reverse
+        if model == CraneModel::CrateMover9000 {
+            crates.reverse();
+        }
+        self.stacks[to].append(&mut crates);
 
         Ok(())
     }
 }
 
+/// Draws the yard the way the puzzle does, with every line padded to the full width of the yard, e.g.
+///
+/// ```text
+///     [D]
+/// [N] [C]
+/// [Z] [M] [P]
+///  1   2   3
+/// ```
+impl fmt::Display for Yard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.draw(f, &[])
+    }
+}
+
+impl Yard {
+    /// Draws the yard, showing the crates at the given `(stack, level)` positions in inverse video.
+    ///
+    /// Every stack gets a column wide enough for its longest label and its number, so wide yards stay aligned.
+    fn draw(&self, f: &mut fmt::Formatter<'_>, highlighted: &[(usize, usize)]) -> fmt::Result {
+        let label_width = self
+            .stacks
+            .iter()
+            .flatten()
+            .map(|crate_| crate_.chars().count())
+            .max()
+            .unwrap_or(1);
+        let id_width = self.stacks.len().to_string().len();
+        let column_width = label_width.max(id_width) + 2;
+
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+        for level in (0..height).rev() {
+            let row: Vec<String> = self
+                .stacks
+                .iter()
+                .enumerate()
+                .map(|(idx, stack)| match stack.get(level) {
+                    Some(crate_) => {
+                        let padding = " ".repeat(column_width - crate_.chars().count() - 2);
+                        if highlighted.contains(&(idx, level)) {
+                            format!("\x1b[7m[{}]\x1b[0m{}", crate_, padding)
+                        } else {
+                            format!("[{}]{}", crate_, padding)
+                        }
+                    }
+                    None => " ".repeat(column_width),
+                })
+                .collect();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+        let footer: Vec<String> = (1..=self.stacks.len())
+            .map(|id| format!(" {:<width$} ", id, width = column_width - 2))
+            .collect();
+        writeln!(f, "{}", footer.join(" "))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 struct Action {
     from: u32,
@@ -70,62 +295,141 @@ struct Action {
     count: u32,
 }
 
-/// Parses a Yard from string input.
-fn parse_yard(content: &str) -> Result<Yard> {
-    let rows: Vec<_> = content
-        .split("\n")
-        .map_while(|line| if line.len() == 0 { None } else { Some(line) })
-        .map(|line| parse_yard_line(line))
-        .collect::<Result<_>>()?;
-
-    let mut yard = Yard::new();
-
-    // The bottom row must be the maximum size
-    let num_stacks = rows.last().ok_or(anyhow!("no rows found"))?.len();
-    for _ in 0..num_stacks {
-        yard.stacks.push(Vec::new());
+/// Parses a Yard from the drawing at the start of the input, with crate labels of `label_width` characters.
+///
+/// The stacks are found from the numbers on the drawing's last line, every crate must be drawn above one of them.
+fn parse_yard(content: &str, label_width: usize) -> Result<Yard> {
+    if label_width == 0 {
+        bail!("crate labels need at least one character");
     }
+    let lines: Vec<&str> = content
+        .split('\n')
+        .map_while(|line| if line.is_empty() { None } else { Some(line) })
+        .collect();
+    let (footer, rows) = lines.split_last().ok_or(anyhow!("no rows found"))?;
+    let columns =
+        parse_yard_footer(footer).map_err(|why| anyhow!("line {}, {}", lines.len(), why))?;
+
+    let mut yard = Yard {
+        stacks: vec![Vec::new(); columns.len()],
+    };
 
-    // Stacks were read top down, reverse them to build them in the right order; so the first row is the top of each stack.
-    // The last row is also not defining crates but the stacks' IDs, skip it.
-    for row in rows.iter().rev().skip(1) {
-        row.iter().enumerate().try_for_each(|(i, c)| match c {
-            'A'..='Z' => {
-                yard.stacks[i].push(*c);
-                Ok(())
+    // Rows were read top down, go bottom up to build the stacks in the right order; so the first row is the top of each stack.
+    for (idx, line) in rows.iter().enumerate().rev() {
+        let row = parse_yard_line(line, label_width, &columns)
+            .map_err(|why| anyhow!("line {}, {}", idx + 1, why))?;
+        let below = rows.len() - 1 - idx;
+        for (stack, column, crate_) in row {
+            if yard.stacks[stack].len() != below {
+                bail!(
+                    "line {}, column {}: crate [{}] has nothing under it",
+                    idx + 1,
+                    column + 1,
+                    crate_
+                );
             }
-            ' ' => Ok(()),
-            _ => Err(anyhow!("invalid crate {}", c)),
-        })?;
+            yard.stacks[stack].push(crate_);
+        }
     }
 
     Ok(yard)
 }
 
-/// Parses one row from a Yard's string representation.
-fn parse_yard_line(line: &str) -> Result<Vec<char>> {
-    let mut stack_idx = 0;
-    let mut row = Vec::new();
-    loop {
-        let pos = stack_idx * 4;
-
-        if pos > line.len() {
-            break;
+/// Parses the stack numbers below a Yard's drawing, which must count up from 1.
+///
+/// Returns the columns each number spans, as start and end character positions.
+fn parse_yard_footer(line: &str) -> Result<Vec<(usize, usize)>> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut columns = Vec::new();
+    let mut pos = 0;
+    while pos < chars.len() {
+        match chars[pos] {
+            ' ' => pos += 1,
+            '0'..='9' => {
+                let start = pos;
+                while chars.get(pos).is_some_and(char::is_ascii_digit) {
+                    pos += 1;
+                }
+                let id: String = chars[start..pos].iter().collect();
+                if id.parse::<usize>().ok() != Some(columns.len() + 1) {
+                    bail!(
+                        "column {}: expected stack number {}, found {}",
+                        start + 1,
+                        columns.len() + 1,
+                        id
+                    );
+                }
+                columns.push((start, pos));
+            }
+            '[' => bail!("column {}: missing the stack numbers", pos + 1),
+            c => bail!("column {}: invalid stack number '{}'", pos + 1, c),
         }
+    }
+    Ok(columns)
+}
 
-        let next_crate = &line[pos..pos + 3];
-        row.push(
-            next_crate
-                .chars()
-                .nth(1)
-                .ok_or(anyhow!("failed to read crate"))?,
-        );
-
-        stack_idx += 1;
+/// Parses one row from a Yard's string representation into `(stack, column, crate)`, left to right.
+///
+/// A crate belongs to the stack whose number shares a column with its label.
+fn parse_yard_line(
+    line: &str,
+    label_width: usize,
+    columns: &[(usize, usize)],
+) -> Result<Vec<(usize, usize, Crate)>> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut row: Vec<(usize, usize, Crate)> = Vec::new();
+    let mut pos = 0;
+    while pos < chars.len() {
+        match chars[pos] {
+            ' ' => pos += 1,
+            '[' => {
+                let end = pos + 1 + label_width;
+                let label: Crate = chars[pos + 1..end.min(chars.len())].iter().collect();
+                if chars.get(end) != Some(&']') || !label.chars().all(char::is_alphanumeric) {
+                    bail!(
+                        "column {}: expected a crate label of {} letters or digits",
+                        pos + 1,
+                        label_width
+                    );
+                }
+
+                let stacks: Vec<usize> = columns
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (start, stop))| *start < end && pos + 1 < *stop)
+                    .map(|(stack, _)| stack)
+                    .collect();
+                let [stack] = stacks[..] else {
+                    bail!(
+                        "column {}: crate [{}] is not aligned with a stack number",
+                        pos + 1,
+                        label
+                    );
+                };
+                if row.last().is_some_and(|(last, _, _)| *last == stack) {
+                    bail!(
+                        "column {}: crate [{}] shares stack {} with another crate",
+                        pos + 1,
+                        label,
+                        stack + 1
+                    );
+                }
+
+                row.push((stack, pos, label));
+                pos = end + 1;
+            }
+            c => bail!("column {}: invalid crate '{}'", pos + 1, c),
+        }
     }
     Ok(row)
 }
 
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
 fn parse_action(line: &str) -> Result<Action> {
     if !line.starts_with("move") {
         return Err(anyhow!("line does not start with the Action prefix"));
@@ -136,26 +440,133 @@ fn parse_action(line: &str) -> Result<Action> {
         return Err(anyhow!("line has wrong number of components for an Action"));
     }
 
-    return Ok(Action {
+    Ok(Action {
         count: components[1].parse()?,
         // Defined stack indices are not zero indexed
         from: components[3].parse::<u32>()?,
         to: components[5].parse::<u32>()?,
-    });
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    const EXAMPLE_YARD: &str = "    [D]    \n\
+                                [N] [C]    \n\
+                                [Z] [M] [P]\n \
+                                1   2   3 \n";
+
+    #[test]
+    fn test_yard_display() -> Result<()> {
+        let yard = parse_yard(EXAMPLE_YARD, 1)?;
+        assert_eq!(yard.to_string(), EXAMPLE_YARD);
+
+        let empty = Yard {
+            stacks: vec![vec![], vec![]],
+        };
+        assert_eq!(empty.to_string(), " 1   2 \n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_wide_yard() -> Result<()> {
+        let drawing = "                                        [CD]     \n\
+                       [AB]                                    [GH] [EF]\n \
+                       1    2    3    4    5    6    7    8    9    10 \n";
+        let yard = parse_yard(drawing, 2)?;
+        assert_eq!(yard.stacks.len(), 10);
+        assert_eq!(yard.stacks[0], ["AB"]);
+        assert_eq!(yard.stacks[8], ["GH", "CD"]);
+        assert_eq!(yard.stacks[9], ["EF"]);
+        assert_eq!(yard.to_string(), drawing);
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn prop_yard_display_roundtrips(
+            stacks in prop::collection::vec(prop::collection::vec("[A-Z]", 0..8), 1..10)
+        ) {
+            let yard = Yard { stacks };
+            prop_assert_eq!(parse_yard(&yard.to_string(), 1).unwrap().stacks, yard.stacks);
+        }
+
+        #[test]
+        fn prop_wide_yard_display_roundtrips(
+            (label_width, stacks) in (1..4usize).prop_flat_map(|width| {
+                let label = prop::string::string_regex(&format!("[A-Z0-9]{{{}}}", width)).unwrap();
+                (Just(width), prop::collection::vec(prop::collection::vec(label, 0..5), 1..40))
+            })
+        ) {
+            let yard = Yard { stacks };
+            prop_assert_eq!(parse_yard(&yard.to_string(), label_width).unwrap().stacks, yard.stacks);
+        }
+    }
 
     #[test]
     fn test_parse_line() -> Result<()> {
-        assert_eq!(parse_yard_line("[A] [B]")?, ['A', 'B']);
-        assert_eq!(parse_yard_line("[A]     [B]")?, ['A', ' ', 'B']);
-        assert_eq!(parse_yard_line("    [A] [B]")?, [' ', 'A', 'B']);
+        let columns = parse_yard_footer(" 1   2   3 ")?;
+        assert_eq!(columns, [(1, 2), (5, 6), (9, 10)]);
+        let stacks = |line: &str| -> Result<Vec<(usize, Crate)>> {
+            Ok(parse_yard_line(line, 1, &columns)?
+                .into_iter()
+                .map(|(stack, _, crate_)| (stack, crate_))
+                .collect())
+        };
+        assert_eq!(stacks("[A] [B]")?, [(0, "A".into()), (1, "B".into())]);
+        assert_eq!(stacks("[A]     [B]")?, [(0, "A".into()), (2, "B".into())]);
+        assert_eq!(stacks("    [A] [B]")?, [(1, "A".into()), (2, "B".into())]);
+        // Trailing spaces may be left out or added
+        assert_eq!(stacks("    [A]")?, [(1, "A".into())]);
+        assert_eq!(stacks("    [A]         ")?, [(1, "A".into())]);
         Ok(())
     }
 
+    #[test]
+    fn test_parse_yard_fails() {
+        let error = |drawing: &str, label_width: usize| {
+            parse_yard(drawing, label_width).unwrap_err().to_string()
+        };
+        assert_eq!(
+            error("  [A]\n 1   2 \n", 1),
+            "line 1, column 3: crate [A] is not aligned with a stack number"
+        );
+        assert_eq!(
+            error("[A]\n    \n 1   2 \n", 1),
+            "line 1, column 1: crate [A] has nothing under it"
+        );
+        assert_eq!(
+            error("[AB]\n 1   2 \n", 1),
+            "line 1, column 1: expected a crate label of 1 letters or digits"
+        );
+        assert_eq!(
+            error("[A\n 1 \n", 1),
+            "line 1, column 1: expected a crate label of 1 letters or digits"
+        );
+        assert_eq!(
+            error("[A] -\n 1   2 \n", 1),
+            "line 1, column 5: invalid crate '-'"
+        );
+        assert_eq!(
+            error("[A] [B]\n 1 \n", 1),
+            "line 1, column 5: crate [B] is not aligned with a stack number"
+        );
+        assert_eq!(
+            error("[AB][CD]\n 1234 \n", 2),
+            "line 2, column 2: expected stack number 1, found 1234"
+        );
+        assert_eq!(
+            error("[A] [B]\n 1   3 \n", 1),
+            "line 2, column 6: expected stack number 2, found 3"
+        );
+        assert_eq!(
+            error("[A] [B]\n", 1),
+            "line 1, column 1: missing the stack numbers"
+        );
+    }
+
     #[test]
     fn test_parse_action() -> Result<()> {
         assert_eq!(
@@ -186,55 +597,97 @@ mod tests {
 
     #[test]
     fn test_crane_lift() -> Result<()> {
-        let get_tops = |yard: &Yard| -> Vec<char> {
-            return yard
-                .stacks
+        let get_tops = |yard: &Yard| -> String {
+            yard.stacks
                 .iter()
-                .map(|stack| stack.last())
-                .map(|last_or_none| match last_or_none {
-                    Some(crate_) => *crate_,
-                    None => ' ',
-                })
-                .collect();
+                .map(|stack| stack.last().map_or(" ", String::as_str))
+                .collect()
+        };
+        // One single letter crate per character, bottom first
+        let yard = |stacks: &[&str]| Yard {
+            stacks: stacks
+                .iter()
+                .map(|stack| stack.chars().map(String::from).collect())
+                .collect(),
         };
 
         // It can move crates
         {
-            let mut yard = Yard {
-                stacks: vec![vec!['A'], vec!['B']],
-            };
-            yard.crane_lift(parse_action("move 1 from 1 to 2")?)?;
-            assert_eq!(get_tops(&yard), vec![' ', 'A']);
+            let mut yard = yard(&["A", "B"]);
+            yard.crane_lift(
+                &parse_action("move 1 from 1 to 2")?,
+                CraneModel::CrateMover9001,
+            )?;
+            assert_eq!(get_tops(&yard), " A");
         }
 
         // It can move multiple crates
         {
-            let mut yard = Yard {
-                stacks: vec![vec!['A'], vec!['B', 'C', 'D']],
-            };
-            yard.crane_lift(parse_action("move 2 from 2 to 1")?)?;
-            assert_eq!(get_tops(&yard), vec!['D', 'B']);
+            let mut yard = yard(&["A", "BCD"]);
+            yard.crane_lift(
+                &parse_action("move 2 from 2 to 1")?,
+                CraneModel::CrateMover9001,
+            )?;
+            assert_eq!(get_tops(&yard), "DB");
+            assert_eq!(yard.stacks[0], ["A", "C", "D"]);
+        }
+
+        // One crate at a time reverses their order
+        {
+            let mut yard = yard(&["A", "BCD"]);
+            yard.crane_lift(
+                &parse_action("move 2 from 2 to 1")?,
+                CraneModel::CrateMover9000,
+            )?;
+            assert_eq!(get_tops(&yard), "CB");
+            assert_eq!(yard.stacks[0], ["A", "D", "C"]);
         }
 
         // It can move crates to empty stacks
         {
-            let mut yard = Yard {
-                stacks: vec![vec!['A'], vec![]],
-            };
-            yard.crane_lift(parse_action("move 1 from 1 to 2")?)?;
-            assert_eq!(get_tops(&yard), vec![' ', 'A']);
+            let mut yard = yard(&["A", ""]);
+            yard.crane_lift(
+                &parse_action("move 1 from 1 to 2")?,
+                CraneModel::CrateMover9001,
+            )?;
+            assert_eq!(get_tops(&yard), " A");
         }
 
         // It fails to move a crate from an empty stack
         {
-            let mut yard = Yard {
-                stacks: vec![vec![], vec!['B']],
-            };
+            let mut yard = yard(&["", "B"]);
             assert!(yard
-                .crane_lift(parse_action("move 1 from 1 to 2")?)
+                .crane_lift(
+                    &parse_action("move 1 from 1 to 2")?,
+                    CraneModel::CrateMover9001
+                )
                 .is_err());
         }
 
+        // It fails on stacks that don't exist, without losing crates
+        {
+            let mut yard = yard(&["A", "B"]);
+            let model = CraneModel::CrateMover9001;
+            assert!(yard
+                .crane_lift(&parse_action("move 1 from 0 to 2")?, model)
+                .is_err());
+            assert!(yard
+                .crane_lift(&parse_action("move 1 from 1 to 3")?, model)
+                .is_err());
+            assert_eq!(get_tops(&yard), "AB");
+        }
+
+        // It refuses to move crates onto their own stack, whichever the crane
+        {
+            let mut yard = yard(&["ABC", "D"]);
+            for model in [CraneModel::CrateMover9000, CraneModel::CrateMover9001] {
+                assert!(yard
+                    .crane_lift(&parse_action("move 2 from 1 to 1")?, model)
+                    .is_err());
+            }
+            assert_eq!(yard.stacks[0], ["A", "B", "C"]);
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,292 @@
+use crate::{Action, CraneModel, Crate, Yard};
+use anyhow::{bail, Result};
+use std::fmt;
+
+/// What one action did to the yard, enough to take it back.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Effect {
+    pub action: Action,
+    /// The crates as they landed on the destination stack, bottom first.
+    pub crates: Vec<Crate>,
+}
+
+/// How one stack differs between two steps.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StackDiff {
+    /// Zero indexed.
+    pub stack: usize,
+    /// Crates above the part both steps share, bottom first.
+    pub removed: Vec<Crate>,
+    pub added: Vec<Crate>,
+}
+
+/// Replays a procedure one action at a time, in either direction.
+///
+/// Step `n` is the yard after the first `n` actions, so step 0 is the starting yard.
+pub struct Replay {
+    yard: Yard,
+    model: CraneModel,
+    effects: Vec<Effect>,
+    step: usize,
+}
+
+impl Replay {
+    /// Runs the whole procedure once to record every action's effect, then rewinds to the start.
+    pub fn new(start: Yard, actions: &[Action], model: CraneModel) -> Result<Self> {
+        let mut yard = start.clone();
+        let mut effects = Vec::with_capacity(actions.len());
+        for (idx, action) in actions.iter().enumerate() {
+            yard.crane_lift(action, model)
+                .map_err(|why| why.context(format!("move {}", idx + 1)))?;
+            let to = &yard.stacks[(action.to - 1) as usize];
+            effects.push(Effect {
+                action: action.clone(),
+                crates: to[to.len() - action.count as usize..].to_vec(),
+            });
+        }
+        Ok(Self {
+            yard: start,
+            model,
+            effects,
+            step: 0,
+        })
+    }
+
+    pub fn yard(&self) -> &Yard {
+        &self.yard
+    }
+
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Returns the number of actions, which is also the last step.
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    /// Returns the effect of the action that led to the current step.
+    pub fn last_effect(&self) -> Option<&Effect> {
+        self.step.checked_sub(1).map(|idx| &self.effects[idx])
+    }
+
+    /// Applies the next action, returning false at the end.
+    pub fn forward(&mut self) -> bool {
+        let Some(effect) = self.effects.get(self.step) else {
+            return false;
+        };
+        let action = &effect.action;
+        let from = &mut self.yard.stacks[(action.from - 1) as usize];
+        let mut crates = from.split_off(from.len() - action.count as usize);
+        if self.model == CraneModel::CrateMover9000 {
+            crates.reverse();
+        }
+        self.yard.stacks[(action.to - 1) as usize].append(&mut crates);
+        self.step += 1;
+        true
+    }
+
+    /// Takes back the last action, returning false at the start.
+    pub fn back(&mut self) -> bool {
+        let Some(effect) = self.last_effect() else {
+            return false;
+        };
+        let action = effect.action.clone();
+        let to = &mut self.yard.stacks[(action.to - 1) as usize];
+        let mut crates = to.split_off(to.len() - action.count as usize);
+        if self.model == CraneModel::CrateMover9000 {
+            crates.reverse();
+        }
+        self.yard.stacks[(action.from - 1) as usize].append(&mut crates);
+        self.step -= 1;
+        true
+    }
+
+    /// Moves forward or backward to `step`.
+    pub fn seek(&mut self, step: usize) -> Result<()> {
+        if step > self.len() {
+            bail!("step {} is past the last step {}", step, self.len());
+        }
+        while self.step < step {
+            self.forward();
+        }
+        while self.step > step {
+            self.back();
+        }
+        Ok(())
+    }
+
+    /// Returns the yard at `step`, leaving the replay where it is.
+    pub fn yard_at(&mut self, step: usize) -> Result<Yard> {
+        let current = self.step;
+        self.seek(step)?;
+        let yard = self.yard.clone();
+        self.seek(current)?;
+        Ok(yard)
+    }
+
+    /// Returns the stacks that differ between two steps.
+    pub fn diff(&mut self, a: usize, b: usize) -> Result<Vec<StackDiff>> {
+        let (before, after) = (self.yard_at(a)?, self.yard_at(b)?);
+        Ok(before
+            .stacks
+            .iter()
+            .zip(&after.stacks)
+            .enumerate()
+            .filter(|(_, (before, after))| before != after)
+            .map(|(stack, (before, after))| {
+                let shared = before
+                    .iter()
+                    .zip(after.iter())
+                    .take_while(|(x, y)| x == y)
+                    .count();
+                StackDiff {
+                    stack,
+                    removed: before[shared..].to_vec(),
+                    added: after[shared..].to_vec(),
+                }
+            })
+            .collect())
+    }
+}
+
+/// Draws the current yard with the crates moved by the last action highlighted.
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let highlighted: Vec<(usize, usize)> = match self.last_effect() {
+            Some(effect) => {
+                let to = (effect.action.to - 1) as usize;
+                let height = self.yard.stacks[to].len();
+                (height - effect.crates.len()..height)
+                    .map(|level| (to, level))
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        self.yard.draw(f, &highlighted)
+    }
+}
+
+impl fmt::Display for StackDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let crates = |crates: &[Crate]| -> String {
+            if crates.is_empty() {
+                String::from("nothing")
+            } else {
+                crates.concat()
+            }
+        };
+        write!(
+            f,
+            "stack {}: -{} +{}",
+            self.stack + 1,
+            crates(&self.removed),
+            crates(&self.added)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_action, parse_yard};
+
+    const EXAMPLE: &str = "    [D]    \n\
+                           [N] [C]    \n\
+                           [Z] [M] [P]\n \
+                           1   2   3 \n\
+                           \n\
+                           move 1 from 2 to 1\n\
+                           move 3 from 1 to 3\n\
+                           move 2 from 2 to 1\n\
+                           move 1 from 1 to 2\n";
+
+    fn example(model: CraneModel) -> Result<Replay> {
+        let actions: Vec<Action> = EXAMPLE
+            .lines()
+            .filter(|line| line.starts_with("move"))
+            .map(parse_action)
+            .collect::<Result<_>>()?;
+        Replay::new(parse_yard(EXAMPLE, 1)?, &actions, model)
+    }
+
+    #[test]
+    fn test_seek_matches_crane_lift() -> Result<()> {
+        for model in [CraneModel::CrateMover9000, CraneModel::CrateMover9001] {
+            let mut replay = example(model)?;
+            let mut yard = parse_yard(EXAMPLE, 1)?;
+            for step in 1..=replay.len() {
+                yard.crane_lift(&replay.effects[step - 1].action.clone(), model)?;
+                replay.seek(step)?;
+                assert_eq!(replay.yard().stacks, yard.stacks);
+            }
+            // And all the way back again
+            replay.seek(0)?;
+            assert_eq!(replay.yard().stacks, parse_yard(EXAMPLE, 1)?.stacks);
+            assert!(!replay.back());
+            assert!(replay.seek(5).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_self_moves() -> Result<()> {
+        let actions = [parse_action("move 2 from 2 to 2")?];
+        assert!(Replay::new(
+            parse_yard(EXAMPLE, 1)?,
+            &actions,
+            CraneModel::CrateMover9000
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_effects() -> Result<()> {
+        let mut replay = example(CraneModel::CrateMover9000)?;
+        replay.seek(2)?;
+        assert_eq!(replay.last_effect().unwrap().crates, ["D", "N", "Z"]);
+        let mut replay = example(CraneModel::CrateMover9001)?;
+        replay.seek(2)?;
+        assert_eq!(replay.last_effect().unwrap().crates, ["Z", "N", "D"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff() -> Result<()> {
+        let mut replay = example(CraneModel::CrateMover9000)?;
+        replay.seek(4)?;
+        assert_eq!(
+            replay.diff(0, 1)?,
+            [
+                StackDiff {
+                    stack: 0,
+                    removed: vec![],
+                    added: vec!["D".into()]
+                },
+                StackDiff {
+                    stack: 1,
+                    removed: vec!["D".into()],
+                    added: vec![]
+                },
+            ]
+        );
+        assert_eq!(replay.diff(0, 1)?[0].to_string(), "stack 1: -nothing +D");
+        // Diffing leaves the replay where it was
+        assert_eq!(replay.step(), 4);
+        assert!(replay.diff(2, 2)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_highlight() -> Result<()> {
+        let mut replay = example(CraneModel::CrateMover9001)?;
+        assert_eq!(replay.to_string(), replay.yard().to_string());
+        replay.forward();
+        assert_eq!(
+            replay.to_string(),
+            "\x1b[7m[D]\x1b[0m        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{parse_action, Action, Yard};
+use std::fmt;
+
+/// Why a move can't be carried out as written.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Problem {
+    /// The line isn't a move at all.
+    Unreadable {
+        why: String,
+    },
+    ZeroCount,
+    SelfMove,
+    NoSuchStack {
+        stack: u32,
+    },
+    NotEnoughCrates {
+        stack: u32,
+        available: usize,
+    },
+}
+
+/// A move of the procedure that can't be carried out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvalidMove {
+    pub line: usize,
+    /// The move as written.
+    pub text: String,
+    pub problem: Problem,
+}
+
+impl fmt::Display for InvalidMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}: ", self.line, self.text)?;
+        match &self.problem {
+            Problem::Unreadable { why } => write!(f, "{}", why),
+            Problem::ZeroCount => write!(f, "moves no crates"),
+            Problem::SelfMove => write!(f, "moves crates onto the same stack"),
+            Problem::NoSuchStack { stack } => write!(f, "no such stack {}", stack),
+            Problem::NotEnoughCrates { stack, available } => {
+                write!(f, "stack {} only has {} crates", stack, available)
+            }
+        }
+    }
+}
+
+/// Parses and dry-runs a procedure of `(line number, move)` against the starting yard, returning its actions with
+/// their line numbers or every invalid move.
+///
+/// Only stack heights matter to whether a move is possible, so no crates are moved. Invalid moves are skipped and
+/// the rest of the procedure is still checked.
+pub fn validate(
+    yard: &Yard,
+    procedure: &[(usize, &str)],
+) -> Result<Vec<(usize, Action)>, Vec<InvalidMove>> {
+    let mut heights: Vec<usize> = yard.stacks.iter().map(Vec::len).collect();
+    let mut actions = Vec::new();
+    let mut invalid = Vec::new();
+
+    for (line, text) in procedure {
+        let action = match parse_action(text) {
+            Ok(action) => action,
+            Err(why) => {
+                invalid.push(InvalidMove {
+                    line: *line,
+                    text: text.to_string(),
+                    problem: Problem::Unreadable {
+                        why: why.to_string(),
+                    },
+                });
+                continue;
+            }
+        };
+        let stack_index = |stack: u32| {
+            stack
+                .checked_sub(1)
+                .map(|idx| idx as usize)
+                .filter(|idx| *idx < heights.len())
+        };
+        let problem = match (stack_index(action.from), stack_index(action.to)) {
+            (None, _) => Some(Problem::NoSuchStack { stack: action.from }),
+            (_, None) => Some(Problem::NoSuchStack { stack: action.to }),
+            _ if action.count == 0 => Some(Problem::ZeroCount),
+            _ if action.from == action.to => Some(Problem::SelfMove),
+            (Some(from), Some(to)) => {
+                if heights[from] < action.count as usize {
+                    Some(Problem::NotEnoughCrates {
+                        stack: action.from,
+                        available: heights[from],
+                    })
+                } else {
+                    heights[from] -= action.count as usize;
+                    heights[to] += action.count as usize;
+                    None
+                }
+            }
+        };
+        match problem {
+            Some(problem) => invalid.push(InvalidMove {
+                line: *line,
+                text: text.to_string(),
+                problem,
+            }),
+            None => actions.push((*line, action)),
+        }
+    }
+
+    if invalid.is_empty() {
+        Ok(actions)
+    } else {
+        Err(invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let yard = Yard {
+            stacks: vec![vec!["A".into(), "B".into()], vec!["C".into()], vec![]],
+        };
+        let procedure = [
+            "move 1 from 1 to 3",
+            "move 0 from 1 to 2",
+            "move 1 from 2 to 2",
+            "move 1 from 0 to 2",
+            "move 1 from 2 to 4",
+            "move 3 from 2 to 1",
+            "move 2 from 2 to 1",
+            "move x from 1 to 2",
+            "stack 1 on 2",
+        ];
+        let procedure: Vec<(usize, &str)> = procedure
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| (idx + 10, *line))
+            .collect();
+
+        let problems: Vec<_> = validate(&yard, &procedure)
+            .unwrap_err()
+            .into_iter()
+            .map(|invalid| (invalid.line, invalid.problem))
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                (11, Problem::ZeroCount),
+                (12, Problem::SelfMove),
+                (13, Problem::NoSuchStack { stack: 0 }),
+                (14, Problem::NoSuchStack { stack: 4 }),
+                (
+                    15,
+                    Problem::NotEnoughCrates {
+                        stack: 2,
+                        available: 1
+                    }
+                ),
+                (
+                    16,
+                    Problem::NotEnoughCrates {
+                        stack: 2,
+                        available: 1
+                    }
+                ),
+                (
+                    17,
+                    Problem::Unreadable {
+                        why: String::from("invalid digit found in string")
+                    }
+                ),
+                (
+                    18,
+                    Problem::Unreadable {
+                        why: String::from("line does not start with the Action prefix")
+                    }
+                ),
+            ]
+        );
+
+        // A valid procedure comes back as its actions, with their line numbers
+        assert_eq!(
+            validate(&yard, &procedure[..1]),
+            Ok(vec![(10, parse_action("move 1 from 1 to 3").unwrap())])
+        );
+    }
+
+    #[test]
+    fn test_invalid_move_display() {
+        let invalid = InvalidMove {
+            line: 12,
+            text: String::from("move 4 from 2 to 1"),
+            problem: Problem::NotEnoughCrates {
+                stack: 2,
+                available: 3,
+            },
+        };
+        assert_eq!(
+            invalid.to_string(),
+            "line 12: move 4 from 2 to 1: stack 2 only has 3 crates"
+        );
+    }
+}